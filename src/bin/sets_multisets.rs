@@ -0,0 +1,487 @@
+//! Command-line tool for generating benchmark inputs and building and querying Bloom filters.
+//! Build it with `cargo install sets_multisets --features cli`.
+//!
+//! Sets and multisets are written in one of the formats of `sets_multisets::io`:
+//!
+//! - `newline`: one element per line, a multiset repeats an element once per copy.
+//! - `csv`: one `element,count` record per line.
+//! - `binary`: a little-endian header and records, see the `io` module.
+//!
+//! The generators that produce several sets write `set_0`, `set_1`, ... into the output
+//! directory, with the extension `txt`, `csv` or `bin` of the format. The text writers sort the
+//! elements, and with `--seed` the same arguments produce the same files.
+//!
+//! Filters are written in the binary format of `sets_multisets::wire_format`, which records the
+//! hasher, so `query-filter` needs no hasher argument.
+use clap::{Parser, Subcommand, ValueEnum};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sets_multisets::bloom_filters::{
+    gen_bloom_filter_params, Argon2Hasher, Blake3Hasher, BloomFilter, ElementHasher,
+    Shake128Hasher, Xxh3Hasher,
+};
+use sets_multisets::io::{read_binary, read_csv, read_elements, ReadError};
+use sets_multisets::multisets::Multiset;
+use sets_multisets::sets::{
+    try_gen_sets_with_intersection_with_rng, try_gen_sets_with_union_with_rng, Set,
+};
+use sets_multisets::wire_format::{decode_bloom_filter, encode_bloom_filter};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generates sets with an intersection of exactly the given size.
+    Intersection {
+        /// The number of sets to generate.
+        #[arg(long)]
+        set_count: usize,
+        /// The number of elements of every set.
+        #[arg(long)]
+        element_count: usize,
+        /// The elements are drawn from `0..universe`.
+        #[arg(long)]
+        universe: usize,
+        /// The number of elements that all sets share.
+        #[arg(long)]
+        intersection_size: usize,
+        #[command(flatten)]
+        output: SetsOutput,
+    },
+    /// Generates sets with a union of exactly the given size.
+    Union {
+        /// The number of sets to generate.
+        #[arg(long)]
+        set_count: usize,
+        /// The number of elements of every set.
+        #[arg(long)]
+        element_count: usize,
+        /// The elements are drawn from `0..universe`.
+        #[arg(long)]
+        universe: usize,
+        /// The number of elements in at least one set.
+        #[arg(long)]
+        union_size: usize,
+        #[command(flatten)]
+        output: SetsOutput,
+    },
+    /// Generates a multiset with counts drawn uniformly from 1 to the maximum multiplicity.
+    Multiset {
+        /// The number of distinct elements.
+        #[arg(long)]
+        element_count: usize,
+        /// The elements are drawn from `0..universe`.
+        #[arg(long)]
+        universe: usize,
+        /// The largest count of an element.
+        #[arg(long)]
+        max_multiplicity: usize,
+        /// The output file, standard output if omitted.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// The format of the output.
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+        /// Seeds the generator, so that the output is reproducible.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Prints the bin count and hash count, separated by a space, of a Bloom filter for at most
+    /// `max-set-size` elements with a false-positive rate of at most `max-error-rate`.
+    BloomParams {
+        /// The largest false-positive rate, between 0 and 1.
+        #[arg(long)]
+        max_error_rate: f64,
+        /// The largest number of elements that will be inserted.
+        #[arg(long)]
+        max_set_size: usize,
+    },
+    /// Builds a Bloom filter from a set, or from a multiset if `max-multiplicity` is given.
+    BuildFilter {
+        /// The file of the set or multiset.
+        #[arg(long, short)]
+        input: PathBuf,
+        /// The format of the input.
+        #[arg(long, value_enum, default_value_t = Format::Newline)]
+        format: Format,
+        /// The number of bins of the filter, see `bloom-params`.
+        #[arg(long)]
+        bin_count: usize,
+        /// The number of hashes per element, see `bloom-params`.
+        #[arg(long)]
+        hash_count: usize,
+        /// The largest count of an element of the multiset, leave it out for a set.
+        #[arg(long)]
+        max_multiplicity: Option<usize>,
+        /// The hash function, which is recorded in the filter.
+        #[arg(long, value_enum, default_value_t = Hasher::Xxh3)]
+        hasher: Hasher,
+        /// The file to write the filter to.
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+    /// Prints `element<TAB>result` per element, the result is `true` or `false` for a filter of a
+    /// set and the retrieved count for a filter of a multiset.
+    QueryFilter {
+        /// The file written by `build-filter`.
+        #[arg(long, short)]
+        filter: PathBuf,
+        /// The elements to look up.
+        #[arg(required = true)]
+        elements: Vec<usize>,
+    },
+}
+
+#[derive(clap::Args)]
+struct SetsOutput {
+    /// The directory to write the sets to, it is created if needed.
+    #[arg(long, short)]
+    output_dir: PathBuf,
+    /// The format of the sets.
+    #[arg(long, value_enum, default_value_t = Format::Newline)]
+    format: Format,
+    /// Seeds the generator, so that the output is reproducible.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// One element per line, a multiset repeats an element once per copy.
+    Newline,
+    /// One `element,count` record per line, the count defaults to 1.
+    Csv,
+    /// A little-endian header followed by the records, see `sets_multisets::io`.
+    Binary,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Newline => "txt",
+            Format::Csv => "csv",
+            Format::Binary => "bin",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Hasher {
+    Xxh3,
+    Shake128,
+    Blake3,
+    Argon2,
+}
+
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+fn write_set<W: Write>(set: &Set, writer: W, format: Format) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    match format {
+        Format::Newline => set.write_newline(&mut writer)?,
+        Format::Csv => set.write_csv(&mut writer)?,
+        Format::Binary => set.write_binary(&mut writer)?,
+    }
+    writer.flush()
+}
+
+fn write_multiset<W: Write>(multiset: &Multiset, writer: W, format: Format) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    match format {
+        Format::Newline => multiset.write_newline(&mut writer)?,
+        Format::Csv => multiset.write_csv(&mut writer)?,
+        Format::Binary => multiset.write_binary(&mut writer)?,
+    }
+    writer.flush()
+}
+
+fn write_sets(sets: &[Set], output: &SetsOutput) -> io::Result<()> {
+    fs::create_dir_all(&output.output_dir)?;
+    for (i, set) in sets.iter().enumerate() {
+        let path = output
+            .output_dir
+            .join(format!("set_{}.{}", i, output.format.extension()));
+        write_set(set, File::create(path)?, output.format)?;
+    }
+
+    Ok(())
+}
+
+fn read_multiset(path: &Path, format: Format) -> Result<Multiset, ReadError> {
+    let reader = BufReader::new(File::open(path)?);
+    match format {
+        Format::Newline => Multiset::read_newline(reader),
+        Format::Csv => Multiset::read_csv(reader),
+        Format::Binary => Multiset::read_binary(reader),
+    }
+}
+
+/// Streams the elements of a set into the filter, without collecting them first.
+fn build_set_filter<H: ElementHasher>(
+    path: &Path,
+    format: Format,
+    bin_count: usize,
+    hash_count: usize,
+) -> Result<BloomFilter, ReadError> {
+    let reader = BufReader::new(File::open(path)?);
+    let records: Box<dyn Iterator<Item = Result<(usize, usize), ReadError>>> = match format {
+        Format::Newline => {
+            Box::new(read_elements(reader).map(|element| element.map(|element| (element, 1))))
+        }
+        Format::Csv => Box::new(read_csv(reader)),
+        Format::Binary => Box::new(read_binary(reader)?),
+    };
+
+    let mut filter = BloomFilter::new::<H>(bin_count, hash_count);
+    for record in records {
+        let (element, count) = record?;
+        if count > 0 {
+            filter.insert::<H>(&element);
+        }
+    }
+
+    Ok(filter)
+}
+
+fn build_filter<H: ElementHasher>(
+    path: &Path,
+    format: Format,
+    bin_count: usize,
+    hash_count: usize,
+    max_multiplicity: Option<usize>,
+) -> Result<BloomFilter, Box<dyn Error>> {
+    match max_multiplicity {
+        Some(max_multiplicity) => {
+            let multiset = read_multiset(path, format)?;
+            if multiset
+                .element_counts
+                .values()
+                .any(|count| *count > max_multiplicity)
+            {
+                return Err("a count exceeds the maximum multiplicity".into());
+            }
+            Ok(BloomFilter::from_multiset::<H>(
+                &multiset,
+                bin_count,
+                hash_count,
+                max_multiplicity,
+            ))
+        }
+        None => Ok(build_set_filter::<H>(path, format, bin_count, hash_count)?),
+    }
+}
+
+fn query_filter<H: ElementHasher>(filter: &BloomFilter, elements: &[usize]) -> Vec<String> {
+    elements
+        .iter()
+        .map(|element| match filter.max_multiplicity {
+            Some(_) => filter.retrieve_count::<H>(element).to_string(),
+            None => filter.contains::<H>(element).to_string(),
+        })
+        .collect()
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::Intersection {
+            set_count,
+            element_count,
+            universe,
+            intersection_size,
+            output,
+        } => {
+            let sets = try_gen_sets_with_intersection_with_rng(
+                &mut rng(output.seed),
+                set_count,
+                element_count,
+                universe,
+                intersection_size,
+            )?;
+            write_sets(&sets, &output)?;
+        }
+        Command::Union {
+            set_count,
+            element_count,
+            universe,
+            union_size,
+            output,
+        } => {
+            let sets = try_gen_sets_with_union_with_rng(
+                &mut rng(output.seed),
+                set_count,
+                element_count,
+                universe,
+                union_size,
+            )?;
+            write_sets(&sets, &output)?;
+        }
+        Command::Multiset {
+            element_count,
+            universe,
+            max_multiplicity,
+            output,
+            format,
+            seed,
+        } => {
+            let multiset = Multiset::try_random_with_rng(
+                &mut rng(seed),
+                element_count,
+                universe,
+                max_multiplicity,
+            )?;
+            match output {
+                Some(path) => write_multiset(&multiset, File::create(path)?, format)?,
+                None => write_multiset(&multiset, io::stdout().lock(), format)?,
+            }
+        }
+        Command::BloomParams {
+            max_error_rate,
+            max_set_size,
+        } => {
+            if !(max_error_rate > 0. && max_error_rate < 1.) {
+                return Err("the maximum error rate must be between 0 and 1".into());
+            }
+            let (bin_count, hash_count) = gen_bloom_filter_params(max_error_rate, max_set_size);
+            println!("{} {}", bin_count, hash_count);
+        }
+        Command::BuildFilter {
+            input,
+            format,
+            bin_count,
+            hash_count,
+            max_multiplicity,
+            hasher,
+            output,
+        } => {
+            if bin_count == 0 || hash_count == 0 {
+                return Err("the bin count and hash count must be at least 1".into());
+            }
+            let build = match hasher {
+                Hasher::Xxh3 => build_filter::<Xxh3Hasher>,
+                Hasher::Shake128 => build_filter::<Shake128Hasher>,
+                Hasher::Blake3 => build_filter::<Blake3Hasher>,
+                Hasher::Argon2 => build_filter::<Argon2Hasher>,
+            };
+            let filter = build(&input, format, bin_count, hash_count, max_multiplicity)?;
+            fs::write(output, encode_bloom_filter(&filter))?;
+        }
+        Command::QueryFilter { filter, elements } => {
+            let filter = decode_bloom_filter(&fs::read(filter)?)?;
+            let results = match filter.hasher_id {
+                Xxh3Hasher::ID => query_filter::<Xxh3Hasher>(&filter, &elements),
+                Shake128Hasher::ID => query_filter::<Shake128Hasher>(&filter, &elements),
+                Blake3Hasher::ID => query_filter::<Blake3Hasher>(&filter, &elements),
+                Argon2Hasher::ID => query_filter::<Argon2Hasher>(&filter, &elements),
+                id => return Err(format!("unknown hasher id {}", id).into()),
+            };
+
+            for (element, result) in elements.iter().zip(results) {
+                println!("{}\t{}", element, result);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{run, Cli};
+    use clap::Parser;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sets_multisets_{}_{}", std::process::id(), name))
+    }
+
+    fn run_args(args: &[&str]) {
+        run(Cli::parse_from([&["sets_multisets"], args].concat())).unwrap();
+    }
+
+    fn generate_sets(output_dir: &Path, format: &str, seed: &str) {
+        run_args(&[
+            "intersection",
+            "--set-count",
+            "3",
+            "--element-count",
+            "100",
+            "--universe",
+            "10000",
+            "--intersection-size",
+            "10",
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--format",
+            format,
+            "--seed",
+            seed,
+        ]);
+    }
+
+    #[test]
+    fn test_seed_reproduces_sets() {
+        let dir = temp_dir("sets");
+        for (format, extension) in [("newline", "txt"), ("binary", "bin")] {
+            generate_sets(&dir.join("a"), format, "7");
+            generate_sets(&dir.join("b"), format, "7");
+            generate_sets(&dir.join("c"), format, "8");
+
+            for i in 0..3 {
+                let file = format!("set_{}.{}", i, extension);
+                let a = fs::read(dir.join("a").join(&file)).unwrap();
+                assert_eq!(a, fs::read(dir.join("b").join(&file)).unwrap());
+                assert_ne!(a, fs::read(dir.join("c").join(&file)).unwrap());
+            }
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_seed_reproduces_multiset() {
+        let dir = temp_dir("multiset");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["a.csv", "b.csv"] {
+            run_args(&[
+                "multiset",
+                "--element-count",
+                "100",
+                "--universe",
+                "10000",
+                "--max-multiplicity",
+                "5",
+                "-o",
+                dir.join(name).to_str().unwrap(),
+                "--seed",
+                "7",
+            ]);
+        }
+
+        let a = fs::read(dir.join("a.csv")).unwrap();
+        assert!(!a.is_empty());
+        assert_eq!(a, fs::read(dir.join("b.csv")).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+//! Blocked Bloom filters (Putze et al.), where all bits of an element fall within one 512-bit
+//! block, i.e. one cache line. A query touches a single block and compares it against a mask
+//! lane by lane, which compilers turn into SIMD instructions.
+//!
+//! The first hash picks the block and the next `hash_count` hashes pick the bits within it.
+//! Because some blocks receive more elements than others, the false-positive rate is higher than
+//! that of a standard Bloom filter with the same number of bits, see
+//! `gen_blocked_bloom_filter_params`.
+use crate::bloom_filters::ElementHasher;
+use crate::sets::Set;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub const BLOCK_BITS: usize = 512;
+const BLOCK_LANES: usize = BLOCK_BITS / 64;
+
+pub type Block = [u64; BLOCK_LANES];
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockedBloomFilter {
+    pub blocks: Vec<Block>,
+    pub hash_count: usize,
+    pub hasher_id: u8,
+}
+
+/// Returns the index of the element's block and the mask of its bits within that block.
+pub fn blocked_bloom_filter_mask<H: ElementHasher>(
+    element: &usize,
+    block_count: usize,
+    hash_count: usize,
+) -> (usize, Block) {
+    let hashes = H::hash_element_multiple_seeds(
+        element,
+        &(0..=hash_count)
+            .map(|seed| seed as u64)
+            .collect::<Vec<u64>>(),
+    );
+
+    let mut mask = [0; BLOCK_LANES];
+    for hash in &hashes[1..] {
+        let bit = hash % BLOCK_BITS;
+        mask[bit / 64] |= 1 << (bit % 64);
+    }
+
+    (hashes[0] % block_count, mask)
+}
+
+impl BlockedBloomFilter {
+    /// Panics if `block_count` is zero.
+    pub fn new<H: ElementHasher>(block_count: usize, hash_count: usize) -> Self {
+        assert!(block_count > 0);
+
+        BlockedBloomFilter {
+            blocks: vec![[0; BLOCK_LANES]; block_count],
+            hash_count,
+            hasher_id: H::ID,
+        }
+    }
+
+    pub fn from_set<H: ElementHasher>(set: &Set, block_count: usize, hash_count: usize) -> Self {
+        let mut filter = BlockedBloomFilter::new::<H>(block_count, hash_count);
+        for element in &set.elements {
+            filter.insert::<H>(element);
+        }
+
+        filter
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn bin_count(&self) -> usize {
+        self.blocks.len() * BLOCK_BITS
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn insert<H: ElementHasher>(&mut self, element: &usize) {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let (index, mask) =
+            blocked_bloom_filter_mask::<H>(element, self.blocks.len(), self.hash_count);
+        for (lane, mask_lane) in self.blocks[index].iter_mut().zip(mask.iter()) {
+            *lane |= mask_lane;
+        }
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let (index, mask) =
+            blocked_bloom_filter_mask::<H>(element, self.blocks.len(), self.hash_count);
+        let block = &self.blocks[index];
+
+        // Fold all lanes instead of returning early, so that the loop vectorizes
+        (0..BLOCK_LANES).fold(true, |contained, lane| {
+            contained & (block[lane] & mask[lane] == mask[lane])
+        })
+    }
+}
+
+/// The expected false-positive rate of a blocked Bloom filter. The number of elements in the
+/// queried block follows a Poisson distribution with mean `set_size / block_count`.
+pub fn blocked_bloom_filter_error_rate(
+    block_count: usize,
+    hash_count: usize,
+    set_size: usize,
+) -> f64 {
+    let mean = set_size as f64 / block_count as f64;
+    let bit_unset = 1. - 1. / BLOCK_BITS as f64;
+
+    let upper = (mean + 12. * mean.sqrt() + 20.).ceil() as usize;
+    let mut ln_factorial = 0.;
+    let mut error_rate = 0.;
+    for element_count in 0..=upper {
+        if element_count > 0 {
+            ln_factorial += (element_count as f64).ln();
+        }
+
+        let probability = if mean == 0. {
+            if element_count == 0 {
+                1.
+            } else {
+                0.
+            }
+        } else {
+            (element_count as f64 * mean.ln() - mean - ln_factorial).exp()
+        };
+        let block_error_rate =
+            (1. - bit_unset.powf((element_count * hash_count) as f64)).powf(hash_count as f64);
+
+        error_rate += probability * block_error_rate;
+    }
+
+    error_rate
+}
+
+/// For a maximum error rate and maximum set size, returns the smallest block count and the
+/// corresponding hash count for which a blocked Bloom filter assures this maximum error rate.
+/// Panics if `max_error_rate` is not between 0 and 1.
+pub fn gen_blocked_bloom_filter_params(max_error_rate: f64, max_set_size: usize) -> (usize, usize) {
+    assert!(
+        max_error_rate > 0. && max_error_rate < 1.,
+        "the maximum error rate must be between 0 and 1"
+    );
+    // Every block count reaches the error rate, so the search for the smallest would not end
+    if max_set_size == 0 {
+        return (1, 1);
+    }
+
+    let mut h = 1;
+    let mut previous: Option<usize> = None;
+    loop {
+        let current = min_block_count(max_error_rate, max_set_size, h);
+
+        if let Some(p) = previous {
+            if p < current {
+                return (p, h - 1);
+            }
+        }
+
+        h += 1;
+        previous = Some(current);
+    }
+}
+
+/// The smallest block count for which `hash_count` hashes reach the error rate, found by binary
+/// search since the error rate decreases with the block count.
+fn min_block_count(max_error_rate: f64, max_set_size: usize, hash_count: usize) -> usize {
+    let mut upper = 1;
+    while blocked_bloom_filter_error_rate(upper, hash_count, max_set_size) > max_error_rate {
+        upper *= 2;
+    }
+
+    let mut lower = upper / 2;
+    while lower + 1 < upper {
+        let middle = (lower + upper) / 2;
+        if blocked_bloom_filter_error_rate(middle, hash_count, max_set_size) > max_error_rate {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+
+    upper
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocked_bloom_filters::{
+        blocked_bloom_filter_error_rate, gen_blocked_bloom_filter_params, BlockedBloomFilter,
+    };
+    use crate::bloom_filters::{gen_bloom_filter_params, Blake3Hasher, Xxh3Hasher};
+    use crate::sets::Set;
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_contains() {
+        let set = Set::random(1000, 1_000_000);
+        let filter = BlockedBloomFilter::from_set::<H>(&set, 32, 6);
+
+        assert_eq!(filter.bin_count(), 32 * 512);
+        for element in &set.elements {
+            assert!(filter.contains::<H>(element));
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let (block_count, hash_count) = gen_blocked_bloom_filter_params(0.01, 10_000);
+        assert!(blocked_bloom_filter_error_rate(block_count, hash_count, 10_000) <= 0.01);
+        assert!(blocked_bloom_filter_error_rate(block_count - 1, hash_count, 10_000) > 0.01);
+
+        // Blocking needs more bits than a standard Bloom filter
+        let (bin_count, _) = gen_bloom_filter_params(0.01, 10_000);
+        assert!(block_count * 512 > bin_count);
+        assert!(block_count * 512 < 2 * bin_count);
+    }
+
+    #[test]
+    fn test_params_edge_cases() {
+        assert_eq!(gen_blocked_bloom_filter_params(0.01, 0), (1, 1));
+
+        for max_set_size in [1, 2, 100] {
+            let (block_count, hash_count) = gen_blocked_bloom_filter_params(0.5, max_set_size);
+            assert!(blocked_bloom_filter_error_rate(block_count, hash_count, max_set_size) <= 0.5);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the maximum error rate must be between 0 and 1")]
+    fn test_params_error_rate_too_large() {
+        gen_blocked_bloom_filter_params(1., 100);
+    }
+
+    #[test]
+    fn test_error_rate() {
+        let (block_count, hash_count) = gen_blocked_bloom_filter_params(0.02, 5000);
+        let filter =
+            BlockedBloomFilter::from_set::<H>(&(0..5000).collect(), block_count, hash_count);
+
+        let false_positives = (5000..105_000)
+            .filter(|element| filter.contains::<H>(element))
+            .count();
+        assert!(
+            false_positives < 2500,
+            "{} false positives",
+            false_positives
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_hasher() {
+        let filter = BlockedBloomFilter::new::<H>(4, 3);
+        filter.contains::<Blake3Hasher>(&3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let filter = BlockedBloomFilter::from_set::<H>(&Set::new(&[1, 3, 4]), 2, 3);
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BlockedBloomFilter>(&json).unwrap(),
+            filter
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{multisets::Multiset, sets::Set};
 use bytevec::ByteEncodable;
+use std::collections::HashMap;
+use std::ops::Range;
 
 use xxh3::hash64_with_seed;
 
@@ -10,7 +12,18 @@ use sha3::{
 
 use argon2::Argon2;
 
+#[cfg(feature = "serde")]
+use crate::wire_format::{check_parameters, DecodeError};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
 pub trait ElementHasher {
+    /// Identifies the hasher in serialized Bloom filters, so that a filter is never queried
+    /// with a different hasher than the one that built it. The hashers of this crate use 1 to 4.
+    const ID: u8;
+
     fn hash_element(element: &usize, seed: u64) -> usize;
     fn hash_element_multiple_seeds(element: &usize, seeds: &[u64]) -> Vec<usize> {
         seeds
@@ -18,6 +31,26 @@ pub trait ElementHasher {
             .map(|seed| Self::hash_element(element, *seed))
             .collect()
     }
+
+    /// Hashes the pair `(element, index)`, used for the copies of a multiset element. The
+    /// default hashes the hash of the element together with the index, the built-in hashers
+    /// hash both as a single input.
+    fn hash_pair(element: &usize, index: usize, seed: u64) -> usize {
+        Self::hash_element(&(Self::hash_element(element, seed) ^ index), seed)
+    }
+    fn hash_pair_multiple_seeds(element: &usize, index: usize, seeds: &[u64]) -> Vec<usize> {
+        seeds
+            .iter()
+            .map(|seed| Self::hash_pair(element, index, *seed))
+            .collect()
+    }
+}
+
+fn pair_bytes(element: &usize, index: usize) -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&(*element as u64).to_le_bytes());
+    bytes[8..].copy_from_slice(&(index as u64).to_le_bytes());
+    bytes
 }
 
 pub struct Xxh3Hasher;
@@ -29,13 +62,25 @@ pub struct Blake3Hasher;
 pub struct Argon2Hasher;
 
 impl ElementHasher for Xxh3Hasher {
+    const ID: u8 = 1;
+
     fn hash_element(element: &usize, seed: u64) -> usize {
         let element_bytes = (*element as u64).encode::<u64>().unwrap();
         hash64_with_seed(&element_bytes, seed) as usize
     }
+
+    fn hash_pair(element: &usize, index: usize, seed: u64) -> usize {
+        // The seed alone does not decorrelate pairs that share the element, so it is hashed too
+        let mut bytes = [0u8; 24];
+        bytes[..16].copy_from_slice(&pair_bytes(element, index));
+        bytes[16..].copy_from_slice(&seed.to_le_bytes());
+        hash64_with_seed(&bytes, seed) as usize
+    }
 }
 
 impl ElementHasher for Shake128Hasher {
+    const ID: u8 = 2;
+
     fn hash_element(element: &usize, seed: u64) -> usize {
         let element_bytes = (*element as u64).encode::<u64>().unwrap();
         let seed_bytes = seed.encode::<u64>().unwrap();
@@ -49,9 +94,24 @@ impl ElementHasher for Shake128Hasher {
 
         usize::from_ne_bytes(res)
     }
+
+    fn hash_pair(element: &usize, index: usize, seed: u64) -> usize {
+        let seed_bytes = seed.encode::<u64>().unwrap();
+
+        let mut hasher = Shake128::default();
+        hasher.update(&pair_bytes(element, index));
+        hasher.update(&seed_bytes);
+        let mut reader = hasher.finalize_xof();
+        let mut res = [0u8; 8];
+        reader.read(&mut res);
+
+        usize::from_ne_bytes(res)
+    }
 }
 
 impl ElementHasher for Blake3Hasher {
+    const ID: u8 = 3;
+
     fn hash_element(element: &usize, seed: u64) -> usize {
         let element_bytes = (*element as u64).encode::<u64>().unwrap();
         let seed_bytes = seed.encode::<u64>().unwrap();
@@ -65,9 +125,24 @@ impl ElementHasher for Blake3Hasher {
 
         usize::from_ne_bytes(res)
     }
+
+    fn hash_pair(element: &usize, index: usize, seed: u64) -> usize {
+        let seed_bytes = seed.encode::<u64>().unwrap();
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&pair_bytes(element, index));
+        hasher.update(&seed_bytes);
+        let mut reader = hasher.finalize_xof();
+        let mut res = [0u8; 8];
+        reader.fill(&mut res);
+
+        usize::from_ne_bytes(res)
+    }
 }
 
 impl ElementHasher for Argon2Hasher {
+    const ID: u8 = 4;
+
     fn hash_element(element: &usize, seed: u64) -> usize {
         let element_bytes = (*element as u64).encode::<u64>().unwrap();
 
@@ -92,6 +167,61 @@ impl ElementHasher for Argon2Hasher {
             .map(|seed| hash64_with_seed(&res, *seed) as usize)
             .collect()
     }
+
+    fn hash_pair(element: &usize, index: usize, seed: u64) -> usize {
+        Argon2Hasher::hash_pair_multiple_seeds(element, index, &[seed])[0]
+    }
+
+    fn hash_pair_multiple_seeds(element: &usize, index: usize, seeds: &[u64]) -> Vec<usize> {
+        let mut res = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(&pair_bytes(element, index), b"bloom_filter", &mut res)
+            .unwrap();
+
+        seeds
+            .iter()
+            .map(|seed| hash64_with_seed(&res, *seed) as usize)
+            .collect()
+    }
+}
+
+/// How copy `i` of a multiset element is hashed into a Bloom filter.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MultisetEncoding {
+    /// Hashes the pair `(element, i)` with `ElementHasher::hash_pair`.
+    Pair,
+    /// Hashes `element * max_multiplicity + i`, which reproduces filters built before the pair
+    /// encoding. Distinct pairs collide once this exceeds the universe, and elements for which
+    /// it overflows cause a panic.
+    Legacy,
+}
+
+impl MultisetEncoding {
+    /// The hashes of copy `index` of `element`, one per seed.
+    pub fn hash_copy<H: ElementHasher>(
+        &self,
+        element: &usize,
+        index: usize,
+        max_multiplicity: usize,
+        seeds: &[u64],
+    ) -> Vec<usize> {
+        match self {
+            MultisetEncoding::Pair => H::hash_pair_multiple_seeds(element, index, seeds),
+            MultisetEncoding::Legacy => {
+                let encoded = element
+                    .checked_mul(max_multiplicity)
+                    .and_then(|product| product.checked_add(index))
+                    .expect("the element is too large for the legacy multiset encoding");
+                H::hash_element_multiple_seeds(&encoded, seeds)
+            }
+        }
+    }
+}
+
+/// The seeds `0..count`, one per hash.
+pub(crate) fn seeds(count: usize) -> Vec<u64> {
+    (0..count).map(|seed| seed as u64).collect()
 }
 
 /// For a maximum error rate and maximum set size, returns a suitable minimum bin count and hash count. These parameters lead to the lowest possible bin_count that assures this maximum error rate.
@@ -99,10 +229,7 @@ pub fn gen_bloom_filter_params(max_error_rate: f64, max_set_size: usize) -> (usi
     let mut h = 1;
     let mut previous = None;
     loop {
-        let current = (-(h as f64) * (max_set_size as f64 + 0.5)
-            / (1f64 - max_error_rate.powf(1. / (h as f64))).ln()
-            + 1.)
-            .ceil() as usize;
+        let current = bloom_filter_bin_count(max_error_rate, max_set_size, h);
 
         if let Some(p) = previous {
             if p < current {
@@ -117,6 +244,19 @@ pub fn gen_bloom_filter_params(max_error_rate: f64, max_set_size: usize) -> (usi
     }
 }
 
+/// The bin count for which a Bloom filter with `hash_count` hashes of at most `max_set_size`
+/// elements has a false-positive rate of at most `max_error_rate`.
+pub(crate) fn bloom_filter_bin_count(
+    max_error_rate: f64,
+    max_set_size: usize,
+    hash_count: usize,
+) -> usize {
+    (-(hash_count as f64) * (max_set_size as f64 + 0.5)
+        / (1f64 - max_error_rate.powf(1. / (hash_count as f64))).ln()
+        + 1.)
+        .ceil() as usize
+}
+
 /// Same as gen_bloom_filter_params except that the max_error_rate is now log2
 pub fn gen_bloom_filter_params_log2(
     max_error_rate_log2: f64,
@@ -158,6 +298,73 @@ pub fn bloom_filter_indices<H: ElementHasher>(
     .map(move |hash| hash % bin_count)
 }
 
+/// Maps the hashes of `hash` under seeds `0..hash_count` into `0..bin_count`, and keeps drawing
+/// seeds `hash_count, hash_count + 1, ...` until there are `hash_count` distinct indices. The
+/// indices are therefore deterministic for a given element and hasher. Panics if there are more
+/// hashes than bins.
+fn distinct_indices<F: FnMut(&[u64]) -> Vec<usize>>(
+    bin_count: usize,
+    hash_count: usize,
+    mut hash: F,
+) -> Vec<usize> {
+    assert!(hash_count <= bin_count);
+
+    let mut indices: Vec<usize> = Vec::with_capacity(hash_count);
+    let mut seeds = 0..hash_count as u64;
+    while indices.len() < hash_count {
+        for hash in hash(&seeds.clone().collect::<Vec<u64>>()) {
+            let index = hash % bin_count;
+            if indices.len() < hash_count && !indices.contains(&index) {
+                indices.push(index);
+            }
+        }
+
+        seeds = seeds.end..seeds.end + (hash_count - indices.len()) as u64;
+    }
+
+    indices
+}
+
+/// Same as `bloom_filter_indices` if `distinct_indices` is false, and otherwise the indices of
+/// `element` are distinct. Panics if distinct indices are asked for with more hashes than bins.
+pub fn bloom_filter_indices_with_options<H: ElementHasher>(
+    element: &usize,
+    bin_count: usize,
+    hash_count: usize,
+    distinct_indices: bool,
+) -> Vec<usize> {
+    if distinct_indices {
+        self::distinct_indices(bin_count, hash_count, |seeds| {
+            H::hash_element_multiple_seeds(element, seeds)
+        })
+    } else {
+        bloom_filter_indices::<H>(element, bin_count, hash_count).collect()
+    }
+}
+
+/// The indices of copy `index` of `element`, distinct if `distinct_indices` is set.
+pub fn bloom_filter_copy_indices<H: ElementHasher>(
+    element: &usize,
+    index: usize,
+    max_multiplicity: usize,
+    bin_count: usize,
+    hash_count: usize,
+    encoding: MultisetEncoding,
+    distinct_indices: bool,
+) -> Vec<usize> {
+    if distinct_indices {
+        self::distinct_indices(bin_count, hash_count, |seeds| {
+            encoding.hash_copy::<H>(element, index, max_multiplicity, seeds)
+        })
+    } else {
+        encoding
+            .hash_copy::<H>(element, index, max_multiplicity, &seeds(hash_count))
+            .into_iter()
+            .map(|hash| hash % bin_count)
+            .collect()
+    }
+}
+
 pub fn bloom_filter_contains<H: ElementHasher>(
     bins: &[bool],
     element: &usize,
@@ -174,16 +381,73 @@ pub fn bloom_filter_contains<H: ElementHasher>(
     true
 }
 
+/// Same as `bloom_filter_contains`, but for filters built with `distinct_indices`.
+pub fn bloom_filter_contains_with_options<H: ElementHasher>(
+    bins: &[bool],
+    element: &usize,
+    hash_count: usize,
+    distinct_indices: bool,
+) -> bool {
+    bloom_filter_indices_with_options::<H>(element, bins.len(), hash_count, distinct_indices)
+        .into_iter()
+        .all(|index| bins[index])
+}
+
+/// Same as `gen_bloom_filter_params` if `distinct_indices` is false. With distinct indices, an
+/// element leaves a given bin unset with probability `1 - hash_count / bin_count`.
+pub fn gen_bloom_filter_params_with_distinct_indices(
+    max_error_rate: f64,
+    max_set_size: usize,
+    distinct_indices: bool,
+) -> (usize, usize) {
+    if !distinct_indices {
+        return gen_bloom_filter_params(max_error_rate, max_set_size);
+    }
+
+    let mut h = 1;
+    let mut previous = None;
+    loop {
+        let current = (h as f64
+            / -((-max_error_rate.powf(1. / (h as f64))).ln_1p() / (max_set_size as f64 + 0.5))
+                .exp_m1())
+        .ceil() as usize;
+
+        if let Some(p) = previous {
+            if p < current {
+                return (p, h - 1);
+            }
+        }
+
+        h += 1;
+        previous = Some(current);
+    }
+}
+
 impl Set {
     pub fn to_bloom_filter<H: ElementHasher>(
         &self,
         bin_count: usize,
         hash_count: usize,
+    ) -> Vec<bool> {
+        self.to_bloom_filter_with_options::<H>(bin_count, hash_count, false)
+    }
+
+    /// Same as `to_bloom_filter`, but optionally with distinct indices per element.
+    pub fn to_bloom_filter_with_options<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+        distinct_indices: bool,
     ) -> Vec<bool> {
         let mut bins = vec![false; bin_count];
 
         for element in &self.elements {
-            for index in bloom_filter_indices::<H>(element, bin_count, hash_count) {
+            for index in bloom_filter_indices_with_options::<H>(
+                element,
+                bin_count,
+                hash_count,
+                distinct_indices,
+            ) {
                 bins[index] = true;
             }
         }
@@ -193,20 +457,42 @@ impl Set {
 }
 
 impl Multiset {
+    /// Uses `MultisetEncoding::Pair`.
     pub fn to_bloom_filter<H: ElementHasher>(
         &self,
         bin_count: usize,
         hash_count: usize,
         max_multiplicity: usize,
+    ) -> Vec<bool> {
+        self.to_bloom_filter_with_encoding::<H>(
+            bin_count,
+            hash_count,
+            max_multiplicity,
+            MultisetEncoding::Pair,
+            false,
+        )
+    }
+
+    pub fn to_bloom_filter_with_encoding<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+        max_multiplicity: usize,
+        encoding: MultisetEncoding,
+        distinct_indices: bool,
     ) -> Vec<bool> {
         let mut bins = vec![false; bin_count];
 
         for (element, count) in &self.element_counts {
             for i in 0..*count {
-                for index in bloom_filter_indices::<H>(
-                    &(*element * max_multiplicity + i),
+                for index in bloom_filter_copy_indices::<H>(
+                    element,
+                    i,
+                    max_multiplicity,
                     bin_count,
                     hash_count,
+                    encoding,
+                    distinct_indices,
                 ) {
                     bins[index] = true;
                 }
@@ -217,14 +503,45 @@ impl Multiset {
     }
 }
 
+/// Uses `MultisetEncoding::Pair`.
 pub fn bloom_filter_retrieve_count<H: ElementHasher>(
     bins: &[bool],
     element: &usize,
     hash_count: usize,
     max_multiplicity: usize,
+) -> usize {
+    bloom_filter_retrieve_count_with_encoding::<H>(
+        bins,
+        element,
+        hash_count,
+        max_multiplicity,
+        MultisetEncoding::Pair,
+        false,
+    )
+}
+
+pub fn bloom_filter_retrieve_count_with_encoding<H: ElementHasher>(
+    bins: &[bool],
+    element: &usize,
+    hash_count: usize,
+    max_multiplicity: usize,
+    encoding: MultisetEncoding,
+    distinct_indices: bool,
 ) -> usize {
     for i in 0..max_multiplicity {
-        if !bloom_filter_contains::<H>(bins, &(element * max_multiplicity + i), hash_count) {
+        let contained = bloom_filter_copy_indices::<H>(
+            element,
+            i,
+            max_multiplicity,
+            bins.len(),
+            hash_count,
+            encoding,
+            distinct_indices,
+        )
+        .into_iter()
+        .all(|index| bins[index]);
+
+        if !contained {
             return i;
         }
     }
@@ -232,7 +549,354 @@ pub fn bloom_filter_retrieve_count<H: ElementHasher>(
     max_multiplicity
 }
 
+/// Same as `bloom_filter_retrieve_count`, but finds the count with a binary search in
+/// O(log max_multiplicity) membership tests. This assumes that the copies present form a prefix
+/// `0..count`; a false positive beyond the count can make it return a larger count than the
+/// linear scan.
+pub fn bloom_filter_retrieve_count_binary_search<H: ElementHasher>(
+    bins: &[bool],
+    element: &usize,
+    hash_count: usize,
+    max_multiplicity: usize,
+) -> usize {
+    bloom_filter_retrieve_count_binary_search_with_encoding::<H>(
+        bins,
+        element,
+        hash_count,
+        max_multiplicity,
+        MultisetEncoding::Pair,
+        false,
+    )
+}
+
+pub fn bloom_filter_retrieve_count_binary_search_with_encoding<H: ElementHasher>(
+    bins: &[bool],
+    element: &usize,
+    hash_count: usize,
+    max_multiplicity: usize,
+    encoding: MultisetEncoding,
+    distinct_indices: bool,
+) -> usize {
+    // The count lies in lower..=upper, copy lower - 1 is contained and copy upper is not
+    let mut lower = 0;
+    let mut upper = max_multiplicity;
+    while lower < upper {
+        let middle = lower + (upper - lower) / 2;
+        let contained = bloom_filter_copy_indices::<H>(
+            element,
+            middle,
+            max_multiplicity,
+            bins.len(),
+            hash_count,
+            encoding,
+            distinct_indices,
+        )
+        .into_iter()
+        .all(|index| bins[index]);
+
+        if contained {
+            lower = middle + 1;
+        } else {
+            upper = middle;
+        }
+    }
+
+    lower
+}
+
+/// A Bloom filter together with the parameters and hasher needed to query it.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "BloomFilterFields")
+)]
+pub struct BloomFilter {
+    pub bins: Vec<bool>,
+    pub hash_count: usize,
+    /// Only set for filters built from a multiset.
+    pub max_multiplicity: Option<usize>,
+    pub hasher_id: u8,
+    /// Whether every element, or copy of a multiset element, sets `hash_count` distinct bins.
+    pub distinct_indices: bool,
+    /// Only used for filters built from a multiset.
+    pub multiset_encoding: MultisetEncoding,
+}
+
+/// A deserialized `BloomFilter` before its parameters are checked like those of a decoded one.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct BloomFilterFields {
+    bins: Vec<bool>,
+    hash_count: usize,
+    max_multiplicity: Option<usize>,
+    hasher_id: u8,
+    distinct_indices: bool,
+    multiset_encoding: MultisetEncoding,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BloomFilterFields> for BloomFilter {
+    type Error = DecodeError;
+
+    fn try_from(fields: BloomFilterFields) -> Result<Self, DecodeError> {
+        check_parameters(
+            fields.bins.len(),
+            fields.hash_count,
+            fields.max_multiplicity,
+            fields.distinct_indices,
+        )?;
+
+        Ok(BloomFilter {
+            bins: fields.bins,
+            hash_count: fields.hash_count,
+            max_multiplicity: fields.max_multiplicity,
+            hasher_id: fields.hasher_id,
+            distinct_indices: fields.distinct_indices,
+            multiset_encoding: fields.multiset_encoding,
+        })
+    }
+}
+
+impl BloomFilter {
+    pub fn from_set<H: ElementHasher>(set: &Set, bin_count: usize, hash_count: usize) -> Self {
+        BloomFilter::from_set_with_options::<H>(set, bin_count, hash_count, false)
+    }
+
+    /// Same as `from_set`, but optionally with distinct indices per element.
+    pub fn from_set_with_options<H: ElementHasher>(
+        set: &Set,
+        bin_count: usize,
+        hash_count: usize,
+        distinct_indices: bool,
+    ) -> Self {
+        BloomFilter {
+            bins: set.to_bloom_filter_with_options::<H>(bin_count, hash_count, distinct_indices),
+            hash_count,
+            max_multiplicity: None,
+            hasher_id: H::ID,
+            distinct_indices,
+            multiset_encoding: MultisetEncoding::Pair,
+        }
+    }
+
+    pub fn from_multiset<H: ElementHasher>(
+        multiset: &Multiset,
+        bin_count: usize,
+        hash_count: usize,
+        max_multiplicity: usize,
+    ) -> Self {
+        BloomFilter {
+            bins: multiset.to_bloom_filter::<H>(bin_count, hash_count, max_multiplicity),
+            hash_count,
+            max_multiplicity: Some(max_multiplicity),
+            hasher_id: H::ID,
+            distinct_indices: false,
+            multiset_encoding: MultisetEncoding::Pair,
+        }
+    }
+
+    /// Same as `from_multiset`, but with the given encoding and optionally distinct indices.
+    pub fn from_multiset_with_encoding<H: ElementHasher>(
+        multiset: &Multiset,
+        bin_count: usize,
+        hash_count: usize,
+        max_multiplicity: usize,
+        encoding: MultisetEncoding,
+        distinct_indices: bool,
+    ) -> Self {
+        BloomFilter {
+            bins: multiset.to_bloom_filter_with_encoding::<H>(
+                bin_count,
+                hash_count,
+                max_multiplicity,
+                encoding,
+                distinct_indices,
+            ),
+            hash_count,
+            max_multiplicity: Some(max_multiplicity),
+            hasher_id: H::ID,
+            distinct_indices,
+            multiset_encoding: encoding,
+        }
+    }
+
+    /// An empty filter for a set, to be filled with `insert`. It uses neither distinct indices
+    /// nor, for multisets, an encoding other than `MultisetEncoding::Pair`, unless set with
+    /// `with_distinct_indices` and `with_multiset_encoding`.
+    pub fn new<H: ElementHasher>(bin_count: usize, hash_count: usize) -> Self {
+        BloomFilter {
+            bins: vec![false; bin_count],
+            hash_count,
+            max_multiplicity: None,
+            hasher_id: H::ID,
+            distinct_indices: false,
+            multiset_encoding: MultisetEncoding::Pair,
+        }
+    }
+
+    /// Makes every element, or copy of a multiset element, set `hash_count` distinct bins. Only
+    /// call this on an empty filter. Panics if the hash count exceeds the bin count.
+    pub fn with_distinct_indices(mut self, distinct_indices: bool) -> Self {
+        assert!(!distinct_indices || self.hash_count <= self.bins.len());
+
+        self.distinct_indices = distinct_indices;
+        self
+    }
+
+    /// Hashes the copies of multiset elements with the given encoding, e.g. to keep filling a
+    /// filter built with `MultisetEncoding::Legacy`. Only call this on an empty filter.
+    pub fn with_multiset_encoding(mut self, encoding: MultisetEncoding) -> Self {
+        self.multiset_encoding = encoding;
+        self
+    }
+
+    /// An empty filter for a multiset, to be filled with `insert_count`.
+    pub fn new_multiset<H: ElementHasher>(
+        bin_count: usize,
+        hash_count: usize,
+        max_multiplicity: usize,
+    ) -> Self {
+        BloomFilter {
+            max_multiplicity: Some(max_multiplicity),
+            ..BloomFilter::new::<H>(bin_count, hash_count)
+        }
+    }
+
+    /// Builds the same filter as `from_set` from a stream of elements, which may contain
+    /// duplicates, without collecting them first.
+    pub fn from_elements<H: ElementHasher, I: IntoIterator<Item = usize>>(
+        elements: I,
+        bin_count: usize,
+        hash_count: usize,
+    ) -> Self {
+        let mut filter = BloomFilter::new::<H>(bin_count, hash_count);
+        for element in elements {
+            filter.insert::<H>(&element);
+        }
+
+        filter
+    }
+
+    /// Builds the same filter as `from_multiset` from a stream of (element, count) pairs without
+    /// collecting them into a multiset first. An element that occurs in several pairs gets the sum
+    /// of its counts, as with `Multiset::read_csv`, so a running count is kept per element. Panics
+    /// if a sum exceeds `max_multiplicity`.
+    pub fn from_element_counts<H: ElementHasher, I: IntoIterator<Item = (usize, usize)>>(
+        element_counts: I,
+        bin_count: usize,
+        hash_count: usize,
+        max_multiplicity: usize,
+    ) -> Self {
+        let mut filter = BloomFilter::new_multiset::<H>(bin_count, hash_count, max_multiplicity);
+        let mut totals: HashMap<usize, usize> = HashMap::new();
+        for (element, count) in element_counts {
+            let total = totals.entry(element).or_insert(0);
+            filter.insert_copies::<H>(&element, *total..*total + count);
+            *total += count;
+        }
+
+        filter
+    }
+
+    /// Panics if `H` is not the hasher that built this filter or if it was built for a multiset.
+    pub fn insert<H: ElementHasher>(&mut self, element: &usize) {
+        assert_eq!(H::ID, self.hasher_id);
+        assert!(
+            self.max_multiplicity.is_none(),
+            "the Bloom filter was built for a multiset"
+        );
+
+        for index in bloom_filter_indices_with_options::<H>(
+            element,
+            self.bins.len(),
+            self.hash_count,
+            self.distinct_indices,
+        ) {
+            self.bins[index] = true;
+        }
+    }
+
+    /// Inserts the first `count` copies of the element. Panics if `H` is not the hasher that built
+    /// this filter, if it was not built for a multiset, or if `count` exceeds its maximum
+    /// multiplicity.
+    pub fn insert_count<H: ElementHasher>(&mut self, element: &usize, count: usize) {
+        self.insert_copies::<H>(element, 0..count);
+    }
+
+    /// Inserts the given copies of the element, under the same conditions as `insert_count`.
+    fn insert_copies<H: ElementHasher>(&mut self, element: &usize, copies: Range<usize>) {
+        assert_eq!(H::ID, self.hasher_id);
+        let max_multiplicity = self
+            .max_multiplicity
+            .expect("the Bloom filter was not built for a multiset");
+        assert!(copies.end <= max_multiplicity);
+
+        for i in copies {
+            for index in bloom_filter_copy_indices::<H>(
+                element,
+                i,
+                max_multiplicity,
+                self.bins.len(),
+                self.hash_count,
+                self.multiset_encoding,
+                self.distinct_indices,
+            ) {
+                self.bins[index] = true;
+            }
+        }
+    }
+
+    pub fn bin_count(&self) -> usize {
+        self.bins.len()
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        assert_eq!(H::ID, self.hasher_id);
+
+        bloom_filter_contains_with_options::<H>(
+            &self.bins,
+            element,
+            self.hash_count,
+            self.distinct_indices,
+        )
+    }
+
+    /// Panics if `H` is not the hasher that built this filter or if it was not built from a multiset.
+    pub fn retrieve_count<H: ElementHasher>(&self, element: &usize) -> usize {
+        assert_eq!(H::ID, self.hasher_id);
+
+        bloom_filter_retrieve_count_with_encoding::<H>(
+            &self.bins,
+            element,
+            self.hash_count,
+            self.max_multiplicity
+                .expect("the Bloom filter was not built from a multiset"),
+            self.multiset_encoding,
+            self.distinct_indices,
+        )
+    }
+
+    /// Same as `retrieve_count`, but with `bloom_filter_retrieve_count_binary_search`. Panics
+    /// under the same conditions as `retrieve_count`.
+    pub fn retrieve_count_binary_search<H: ElementHasher>(&self, element: &usize) -> usize {
+        assert_eq!(H::ID, self.hasher_id);
+
+        bloom_filter_retrieve_count_binary_search_with_encoding::<H>(
+            &self.bins,
+            element,
+            self.hash_count,
+            self.max_multiplicity
+                .expect("the Bloom filter was not built from a multiset"),
+            self.multiset_encoding,
+            self.distinct_indices,
+        )
+    }
+}
+
 #[cfg(test)]
+#[allow(unused_imports)]
 mod tests {
     use crate::{
         bloom_filters::{bloom_filter_contains, bloom_filter_retrieve_count, Xxh3Hasher},
@@ -240,7 +904,10 @@ mod tests {
         sets::Set,
     };
 
-    use super::{gen_bloom_filter_params, gen_bloom_filter_params_log2};
+    use super::{
+        gen_bloom_filter_params, gen_bloom_filter_params_log2,
+        gen_bloom_filter_params_with_distinct_indices,
+    };
 
     #[test]
     fn test_bf_parameters_smallrate() {
@@ -276,12 +943,38 @@ mod tests {
         assert_eq!(bin_count, 945602);
         assert_eq!(hash_count, 160);
     }
+
+    #[test]
+    fn test_bf_parameters_distinct() {
+        // For large filters, distinct indices barely change the parameters
+        let (bin_count, hash_count) =
+            gen_bloom_filter_params_with_distinct_indices(2f64.powf(-10.), 4096, true);
+        assert_eq!(bin_count, 59106);
+        assert_eq!(hash_count, 10);
+
+        let (bin_count, hash_count) = gen_bloom_filter_params_with_distinct_indices(0.01, 1, true);
+        assert!(bin_count >= hash_count);
+
+        assert_eq!(
+            gen_bloom_filter_params_with_distinct_indices(2f64.powf(-10.), 4096, false),
+            gen_bloom_filter_params(2f64.powf(-10.), 4096)
+        );
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests_xxh3 {
     use crate::bloom_filters::bloom_filter_contains;
+    use crate::bloom_filters::bloom_filter_contains_with_options;
+    use crate::bloom_filters::bloom_filter_indices;
+    use crate::bloom_filters::bloom_filter_indices_with_options;
     use crate::bloom_filters::bloom_filter_retrieve_count;
+    use crate::bloom_filters::bloom_filter_retrieve_count_binary_search;
+    use crate::bloom_filters::bloom_filter_retrieve_count_with_encoding;
+    use crate::bloom_filters::BloomFilter;
+    use crate::bloom_filters::ElementHasher;
+    use crate::bloom_filters::MultisetEncoding;
     use crate::bloom_filters::Xxh3Hasher;
     use crate::multisets::Multiset;
     use crate::sets::Set;
@@ -311,9 +1004,351 @@ mod tests_xxh3 {
         assert_eq!(bloom_filter_retrieve_count::<H>(&bloom_filter, &3, 2, 2), 2);
         assert_eq!(bloom_filter_retrieve_count::<H>(&bloom_filter, &4, 2, 2), 1);
     }
+
+    #[test]
+    fn test_bloom_filter_struct() {
+        let set = Set::new(&[1, 3, 4]);
+        let bloom_filter = BloomFilter::from_set::<H>(&set, 20, 2);
+        assert_eq!(bloom_filter.bin_count(), 20);
+        assert!(bloom_filter.contains::<H>(&3));
+        assert!(!bloom_filter.contains::<H>(&5));
+
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let bloom_filter = BloomFilter::from_multiset::<H>(&multiset, 50, 2, 2);
+        assert_eq!(bloom_filter.retrieve_count::<H>(&3), 2);
+        assert_eq!(bloom_filter.retrieve_count::<H>(&4), 1);
+    }
+
+    #[test]
+    fn test_bloom_filter_from_stream() {
+        let set = Set::random(100, 10_000);
+        let elements = set.elements.iter().chain(set.elements.iter()).copied();
+        assert_eq!(
+            BloomFilter::from_elements::<H, _>(elements, 1000, 3),
+            BloomFilter::from_set::<H>(&set, 1000, 3)
+        );
+
+        let multiset = Multiset::random(100, 10_000, 5);
+        let element_counts = multiset.element_counts.iter().flat_map(|(element, count)| {
+            vec![(*element, count / 2), (*element, count - count / 2)]
+        });
+        assert_eq!(
+            BloomFilter::from_element_counts::<H, _>(element_counts, 2000, 3, 5),
+            BloomFilter::from_multiset::<H>(&multiset, 2000, 3, 5)
+        );
+    }
+
+    #[test]
+    fn test_bloom_filter_from_stream_with_options() {
+        let multiset = Multiset::random(100, 10_000, 5);
+        for (encoding, distinct_indices) in [
+            (MultisetEncoding::Legacy, false),
+            (MultisetEncoding::Legacy, true),
+            (MultisetEncoding::Pair, true),
+        ] {
+            let mut bloom_filter = BloomFilter::new_multiset::<H>(2000, 3, 5)
+                .with_multiset_encoding(encoding)
+                .with_distinct_indices(distinct_indices);
+            for (element, count) in &multiset.element_counts {
+                bloom_filter.insert_count::<H>(element, *count);
+            }
+
+            assert_eq!(
+                bloom_filter,
+                BloomFilter::from_multiset_with_encoding::<H>(
+                    &multiset,
+                    2000,
+                    3,
+                    5,
+                    encoding,
+                    distinct_indices
+                )
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bloom_filter_insert_into_multiset_filter() {
+        BloomFilter::new_multiset::<H>(100, 3, 5).insert::<H>(&3);
+    }
+
+    #[test]
+    fn test_retrieve_count_binary_search() {
+        let multiset = Multiset::random(200, 100_000, 40);
+        let bloom_filter = BloomFilter::from_multiset::<H>(&multiset, 400_000, 7, 40);
+
+        for (element, count) in &multiset.element_counts {
+            assert_eq!(
+                bloom_filter.retrieve_count_binary_search::<H>(element),
+                *count
+            );
+            assert_eq!(
+                bloom_filter_retrieve_count_binary_search::<H>(&bloom_filter.bins, element, 7, 40),
+                *count
+            );
+        }
+        assert_eq!(
+            bloom_filter.retrieve_count_binary_search::<H>(&100_001),
+            bloom_filter.retrieve_count::<H>(&100_001)
+        );
+    }
+
+    #[test]
+    fn test_legacy_encoding() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let bloom_filter =
+            multiset.to_bloom_filter_with_encoding::<H>(50, 2, 2, MultisetEncoding::Legacy, false);
+
+        // The legacy encoding inserts element * max_multiplicity + i as a set element
+        assert_eq!(
+            bloom_filter,
+            Set::new(&[2, 6, 7, 8]).to_bloom_filter::<H>(50, 2)
+        );
+        assert_eq!(
+            bloom_filter_retrieve_count_with_encoding::<H>(
+                &bloom_filter,
+                &3,
+                2,
+                2,
+                MultisetEncoding::Legacy,
+                false
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn test_pair_encoding_large_elements() {
+        let multiset = Multiset::new(&[usize::MAX, usize::MAX / 3], &[3, 1]);
+        let bloom_filter = BloomFilter::from_multiset::<H>(&multiset, 100, 3, 4);
+
+        assert_eq!(bloom_filter.multiset_encoding, MultisetEncoding::Pair);
+        assert_eq!(bloom_filter.retrieve_count::<H>(&usize::MAX), 3);
+        assert_eq!(bloom_filter.retrieve_count::<H>(&(usize::MAX / 3)), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "too large for the legacy multiset encoding")]
+    fn test_legacy_encoding_overflow() {
+        Multiset::new(&[usize::MAX], &[1]).to_bloom_filter_with_encoding::<H>(
+            100,
+            3,
+            4,
+            MultisetEncoding::Legacy,
+            false,
+        );
+    }
+
+    #[test]
+    fn test_default_hash_pair() {
+        struct IdentityHasher;
+
+        impl ElementHasher for IdentityHasher {
+            const ID: u8 = 0;
+
+            fn hash_element(element: &usize, seed: u64) -> usize {
+                element.wrapping_add(seed as usize)
+            }
+        }
+
+        assert_ne!(
+            IdentityHasher::hash_pair(&1, 0, 0),
+            IdentityHasher::hash_pair(&1, 1, 0)
+        );
+        assert_eq!(
+            IdentityHasher::hash_pair_multiple_seeds(&1, 1, &[0, 1]),
+            vec![
+                IdentityHasher::hash_pair(&1, 1, 0),
+                IdentityHasher::hash_pair(&1, 1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distinct_indices() {
+        for element in 0..1000 {
+            let mut bloom_filter = BloomFilter::new::<H>(8, 6).with_distinct_indices(true);
+            bloom_filter.insert::<H>(&element);
+
+            assert_eq!(bloom_filter.bins.iter().filter(|bin| **bin).count(), 6);
+            assert!(bloom_filter.contains::<H>(&element));
+        }
+
+        let mut bloom_filter = BloomFilter::new::<H>(5, 5).with_distinct_indices(true);
+        bloom_filter.insert::<H>(&7);
+        assert!(bloom_filter.bins.iter().all(|bin| *bin));
+    }
+
+    #[test]
+    fn test_distinct_indices_draw_seeds() {
+        // Seed `s` hashes to `s / 2`, so seeds 0 to 6 are drawn for four distinct indices
+        let mut drawn_seeds = Vec::new();
+        let indices = super::distinct_indices(10, 4, |seeds| {
+            drawn_seeds.extend_from_slice(seeds);
+            seeds.iter().map(|seed| *seed as usize / 2).collect()
+        });
+
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        assert_eq!(drawn_seeds, (0..7).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn test_distinct_indices_false_positive_rate() {
+        // Small filters, where distinct indices matter, match the formula of
+        // `gen_bloom_filter_params_with_distinct_indices`
+        let (bin_count, hash_count, element_count) = (128, 4, 20);
+        let filled_fraction =
+            1. - (1. - hash_count as f64 / bin_count as f64).powi(element_count as i32);
+        let expected_rate = filled_fraction.powi(hash_count as i32);
+
+        let mut false_positives = 0;
+        let trial_count = 200;
+        let query_count = 1000;
+        for trial in 0..trial_count {
+            let mut bloom_filter =
+                BloomFilter::new::<H>(bin_count, hash_count).with_distinct_indices(true);
+            let elements = trial * element_count..(trial + 1) * element_count;
+            for element in elements {
+                bloom_filter.insert::<H>(&element);
+            }
+
+            false_positives += (0..query_count)
+                .filter(|query| {
+                    bloom_filter.contains::<H>(&(usize::MAX - trial * query_count - query))
+                })
+                .count();
+        }
+
+        let rate = false_positives as f64 / (trial_count * query_count) as f64;
+        assert!(
+            (rate - expected_rate).abs() < 0.15 * expected_rate,
+            "rate {} expected {}",
+            rate,
+            expected_rate
+        );
+    }
+
+    #[test]
+    fn test_set_to_bloom_filter_distinct() {
+        let set = Set::new(&[1, 3, 4]);
+        let mut bloom_filter = BloomFilter::new::<H>(20, 4).with_distinct_indices(true);
+        for element in &set.elements {
+            bloom_filter.insert::<H>(element);
+        }
+
+        assert!(bloom_filter.distinct_indices);
+        for element in &set.elements {
+            assert!(bloom_filter.contains::<H>(element));
+        }
+
+        assert_eq!(
+            BloomFilter::from_set_with_options::<H>(&set, 20, 4, true),
+            bloom_filter
+        );
+        let bins = set.to_bloom_filter_with_options::<H>(20, 4, true);
+        assert_eq!(bins, bloom_filter.bins);
+        for element in &set.elements {
+            assert!(bloom_filter_contains_with_options::<H>(
+                &bins, element, 4, true
+            ));
+            assert_eq!(
+                bloom_filter_indices_with_options::<H>(element, 20, 4, false),
+                bloom_filter_indices::<H>(element, 20, 4).collect::<Vec<usize>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_multiset_to_bloom_filter_distinct() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let bins =
+            multiset.to_bloom_filter_with_encoding::<H>(50, 2, 2, MultisetEncoding::Pair, true);
+
+        assert_eq!(
+            bloom_filter_retrieve_count_with_encoding::<H>(
+                &bins,
+                &1,
+                2,
+                2,
+                MultisetEncoding::Pair,
+                true
+            ),
+            1
+        );
+        assert_eq!(
+            bloom_filter_retrieve_count_with_encoding::<H>(
+                &bins,
+                &3,
+                2,
+                2,
+                MultisetEncoding::Pair,
+                true
+            ),
+            2
+        );
+
+        let bloom_filter = BloomFilter::from_multiset_with_encoding::<H>(
+            &multiset,
+            50,
+            2,
+            2,
+            MultisetEncoding::Pair,
+            true,
+        );
+        assert_eq!(bloom_filter.retrieve_count::<H>(&3), 2);
+        assert_eq!(bloom_filter.retrieve_count::<H>(&4), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_distinct_indices_too_many_hashes() {
+        BloomFilter::new::<H>(4, 5).with_distinct_indices(true);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bloom_filter_struct_wrong_hasher() {
+        let bloom_filter = BloomFilter::from_set::<H>(&Set::new(&[1, 3, 4]), 20, 2);
+        bloom_filter.contains::<super::Blake3Hasher>(&3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_bloom_filter_serde() {
+        let bloom_filter =
+            BloomFilter::from_multiset::<H>(&Multiset::new(&[1, 3, 4], &[1, 2, 1]), 50, 2, 2);
+        let json = serde_json::to_string(&bloom_filter).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BloomFilter>(&json).unwrap(),
+            bloom_filter
+        );
+
+        // Every field is required, so the encoding is never guessed
+        let without_encoding = json.replace(",\"multiset_encoding\":\"Pair\"", "");
+        assert_ne!(without_encoding, json);
+        assert!(serde_json::from_str::<BloomFilter>(&without_encoding).is_err());
+
+        // The parameters are checked like those of a decoded filter
+        let invalid_filters = [
+            BloomFilter::new::<H>(0, 2),
+            BloomFilter::new::<H>(20, 0),
+            BloomFilter::new_multiset::<H>(20, 2, 0),
+            BloomFilter {
+                distinct_indices: true,
+                ..BloomFilter::new::<H>(3, 4)
+            },
+        ];
+        for invalid_filter in &invalid_filters {
+            let json = serde_json::to_string(invalid_filter).unwrap();
+            let error = serde_json::from_str::<BloomFilter>(&json).unwrap_err();
+            assert_eq!(error.to_string(), "invalid Bloom filter parameters");
+        }
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests_shake128 {
     use super::Shake128Hasher;
     use crate::bloom_filters::bloom_filter_contains;
@@ -349,6 +1384,7 @@ mod tests_shake128 {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests_blake3 {
     use crate::bloom_filters::bloom_filter_contains;
     use crate::bloom_filters::bloom_filter_retrieve_count;
@@ -385,6 +1421,7 @@ mod tests_blake3 {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests_argon2 {
     use crate::bloom_filters::bloom_filter_contains;
     use crate::bloom_filters::bloom_filter_retrieve_count;
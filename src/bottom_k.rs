@@ -0,0 +1,279 @@
+use crate::bloom_filters::ElementHasher;
+use crate::error::Error;
+use crate::sets::Set;
+use std::collections::BTreeSet;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A bottom-k (k minimum values) sketch: the `k` smallest hashes of the elements of a set.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BottomKSketch {
+    pub k: usize,
+    /// Sorted in increasing order, contains fewer than `k` hashes if the set is smaller than `k`.
+    pub hashes: Vec<u64>,
+    pub hasher_id: u8,
+}
+
+impl Set {
+    /// See `BottomKSketch::from_elements`.
+    pub fn to_bottom_k_sketch<H: ElementHasher>(&self, k: usize) -> BottomKSketch {
+        BottomKSketch::from_elements::<H, _>(k, self.elements.iter().copied())
+    }
+}
+
+impl BottomKSketch {
+    /// Builds the sketch from a stream of elements, which may contain duplicates, keeping only
+    /// the `k` smallest hashes seen so far. Panics if `k` is 0.
+    pub fn from_elements<H: ElementHasher, I: IntoIterator<Item = usize>>(
+        k: usize,
+        elements: I,
+    ) -> Self {
+        assert!(k > 0, "k must be at least 1");
+
+        let mut hashes = BTreeSet::new();
+        for element in elements {
+            let hash = H::hash_element(&element, 0) as u64;
+            if hashes.len() < k {
+                hashes.insert(hash);
+            } else if hashes.last().is_some_and(|largest| hash < *largest) && hashes.insert(hash) {
+                hashes.pop_last();
+            }
+        }
+
+        BottomKSketch {
+            k,
+            hashes: hashes.into_iter().collect(),
+            hasher_id: H::ID,
+        }
+    }
+
+    /// Whether the sketch holds every hash of its set, so that estimates are exact.
+    pub fn is_exact(&self) -> bool {
+        self.hashes.len() < self.k
+    }
+
+    pub fn estimate_cardinality(&self) -> f64 {
+        if self.is_exact() {
+            return self.hashes.len() as f64;
+        }
+
+        (self.k - 1) as f64 / unit_interval(self.hashes[self.k - 1])
+    }
+
+    fn contains_hash(&self, hash: &u64) -> bool {
+        self.hashes.binary_search(hash).is_ok()
+    }
+
+    fn check_compatible(sketches: &[BottomKSketch]) -> Result<(), Error> {
+        let first = sketches.first().ok_or(Error::NotEnoughSets {
+            required: 1,
+            given: 0,
+        })?;
+
+        if sketches
+            .iter()
+            .any(|sketch| sketch.k != first.k || sketch.hasher_id != first.hasher_id)
+        {
+            return Err(Error::InvalidParameter(
+                "the sketches differ in their k or hasher",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The sketch of the union of the sets summarized by the sketches. Panics if `sketches` is
+    /// empty or the sketches differ in their `k` or hasher.
+    pub fn union(sketches: &[BottomKSketch]) -> BottomKSketch {
+        BottomKSketch::try_union(sketches).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_union(sketches: &[BottomKSketch]) -> Result<BottomKSketch, Error> {
+        BottomKSketch::check_compatible(sketches)?;
+
+        let mut hashes: Vec<u64> = sketches
+            .iter()
+            .flat_map(|sketch| sketch.hashes.iter().copied())
+            .collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        hashes.truncate(sketches[0].k);
+
+        Ok(BottomKSketch {
+            k: sketches[0].k,
+            hashes,
+            hasher_id: sketches[0].hasher_id,
+        })
+    }
+
+    /// Estimates the Jaccard similarity of all sets as the fraction of the union sketch's hashes
+    /// that appear in every sketch. Panics if `sketches` is empty or the sketches differ in their
+    /// `k` or hasher.
+    pub fn estimate_jaccard(sketches: &[BottomKSketch]) -> f64 {
+        BottomKSketch::try_estimate_jaccard(sketches).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_estimate_jaccard(sketches: &[BottomKSketch]) -> Result<f64, Error> {
+        let union = BottomKSketch::try_union(sketches)?;
+        if union.hashes.is_empty() {
+            return Ok(1.);
+        }
+
+        let shared_count = union
+            .hashes
+            .iter()
+            .filter(|hash| sketches.iter().all(|sketch| sketch.contains_hash(hash)))
+            .count();
+
+        Ok(shared_count as f64 / union.hashes.len() as f64)
+    }
+
+    /// Estimates the size of the intersection of all sets. Panics if `sketches` is empty or the
+    /// sketches differ in their `k` or hasher.
+    pub fn estimate_intersection_size(sketches: &[BottomKSketch]) -> f64 {
+        BottomKSketch::try_estimate_intersection_size(sketches)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_estimate_intersection_size(sketches: &[BottomKSketch]) -> Result<f64, Error> {
+        Ok(BottomKSketch::try_estimate_jaccard(sketches)?
+            * BottomKSketch::try_union(sketches)?.estimate_cardinality())
+    }
+
+    /// Estimates the fraction of this sketch's set that is contained in the other set. Panics if
+    /// the sketches differ in their `k` or hasher.
+    pub fn estimate_containment(&self, other: &BottomKSketch) -> f64 {
+        let size = self.estimate_cardinality();
+        if size == 0. {
+            return 1.;
+        }
+
+        BottomKSketch::estimate_intersection_size(&[self.clone(), other.clone()]) / size
+    }
+}
+
+/// Maps a hash to the interval (0, 1].
+fn unit_interval(hash: u64) -> f64 {
+    (hash as f64 + 1.) / 2f64.powi(64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::Xxh3Hasher;
+    use crate::bottom_k::BottomKSketch;
+    use crate::error::Error;
+    use crate::sets::{gen_sets_with_intersection, Set};
+
+    type H = Xxh3Hasher;
+
+    fn assert_close(estimate: f64, exact: f64, error: f64) {
+        assert!(
+            (estimate - exact).abs() <= error,
+            "estimate {} is too far from {}",
+            estimate,
+            exact
+        );
+    }
+
+    #[test]
+    fn test_from_elements() {
+        let set = Set::random(5000, 1_000_000);
+        let elements = set.elements.iter().chain(set.elements.iter()).copied();
+
+        assert_eq!(
+            BottomKSketch::from_elements::<H, _>(256, elements),
+            set.to_bottom_k_sketch::<H>(256)
+        );
+        assert_eq!(
+            BottomKSketch::from_elements::<H, _>(256, vec![1, 3, 3, 4]),
+            Set::new(&[1, 3, 4]).to_bottom_k_sketch::<H>(256)
+        );
+    }
+
+    #[test]
+    fn test_cardinality() {
+        let set = Set::random(10_000, 1_000_000_000);
+        let sketch = set.to_bottom_k_sketch::<H>(1024);
+
+        assert!(!sketch.is_exact());
+        assert_close(sketch.estimate_cardinality(), 10_000., 1_000.);
+
+        let small_sketch = Set::new(&[1, 3, 4]).to_bottom_k_sketch::<H>(1024);
+        assert!(small_sketch.is_exact());
+        assert_eq!(small_sketch.estimate_cardinality(), 3.);
+    }
+
+    #[test]
+    fn test_intersection_size() {
+        let sets = gen_sets_with_intersection(4, 5000, 1_000_000, 2500);
+        let sketches: Vec<BottomKSketch> = sets
+            .iter()
+            .map(|set| set.to_bottom_k_sketch::<H>(1024))
+            .collect();
+
+        assert_close(
+            BottomKSketch::estimate_intersection_size(&sketches),
+            Set::intersection(&sets).len() as f64,
+            500.,
+        );
+        assert_close(
+            BottomKSketch::union(&sketches).estimate_cardinality(),
+            Set::union(&sets).len() as f64,
+            0.1 * Set::union(&sets).len() as f64,
+        );
+    }
+
+    #[test]
+    fn test_containment() {
+        let set1: Set = (0..2000).collect();
+        let set2: Set = (1000..10000).collect();
+
+        let sketch1 = set1.to_bottom_k_sketch::<H>(1024);
+        let sketch2 = set2.to_bottom_k_sketch::<H>(1024);
+
+        assert_close(sketch1.estimate_containment(&sketch2), 0.5, 0.15);
+        assert_close(sketch2.estimate_containment(&sketch1), 1. / 9., 0.05);
+    }
+
+    #[test]
+    fn test_no_sketches() {
+        let error = Error::NotEnoughSets {
+            required: 1,
+            given: 0,
+        };
+        assert_eq!(BottomKSketch::try_union(&[]).unwrap_err(), error);
+        assert_eq!(BottomKSketch::try_estimate_jaccard(&[]).unwrap_err(), error);
+        assert_eq!(
+            BottomKSketch::try_estimate_intersection_size(&[]).unwrap_err(),
+            error
+        );
+    }
+
+    #[test]
+    fn test_incompatible_sketches() {
+        let set = Set::random(100, 1_000_000);
+        let sketches = [
+            set.to_bottom_k_sketch::<H>(16),
+            set.to_bottom_k_sketch::<H>(32),
+        ];
+        let error = Error::InvalidParameter("the sketches differ in their k or hasher");
+
+        assert_eq!(BottomKSketch::try_union(&sketches).unwrap_err(), error);
+        assert_eq!(
+            BottomKSketch::try_estimate_jaccard(&sketches).unwrap_err(),
+            error
+        );
+        assert_eq!(
+            BottomKSketch::try_estimate_intersection_size(&sketches).unwrap_err(),
+            error
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be at least 1")]
+    fn test_zero_k() {
+        Set::new(&[1, 2, 3]).to_bottom_k_sketch::<H>(0);
+    }
+}
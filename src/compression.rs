@@ -0,0 +1,423 @@
+//! Compressed encodings for sparse Bloom filters, following Mitzenmacher's "Compressed Bloom
+//! Filters". Both encodings start with the bin count as a little-endian `u64`.
+use crate::bloom_filters::{bloom_filter_bin_count, gen_bloom_filter_params};
+use crate::wire_format::DecodeError;
+use std::convert::{TryFrom, TryInto};
+
+/// Writes bits most significant first.
+pub(crate) struct BitWriter {
+    bytes: Vec<u8>,
+    bit_count: usize,
+}
+
+impl BitWriter {
+    pub(crate) fn new() -> Self {
+        BitWriter {
+            bytes: vec![],
+            bit_count: 0,
+        }
+    }
+
+    pub(crate) fn write_bit(&mut self, bit: bool) {
+        if self.bit_count % 8 == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 0x80 >> (self.bit_count % 8);
+        }
+        self.bit_count += 1;
+    }
+
+    /// Writes the `bit_count` least significant bits of `value`.
+    pub(crate) fn write_bits(&mut self, value: u64, bit_count: u8) {
+        for i in (0..bit_count).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    /// Writes `value` as a Golomb-Rice code: the quotient by `2^parameter` in unary, then the
+    /// remainder in `parameter` bits.
+    pub(crate) fn write_rice(&mut self, value: u64, parameter: u8) {
+        for _ in 0..(value >> parameter) {
+            self.write_bit(true);
+        }
+        self.write_bit(false);
+        self.write_bits(value, parameter);
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+pub(crate) struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0 }
+    }
+
+    pub(crate) fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.bytes.get(self.position / 8)?;
+        let bit = (byte << (self.position % 8)) & 0x80 != 0;
+        self.position += 1;
+        Some(bit)
+    }
+
+    pub(crate) fn read_bits(&mut self, bit_count: u8) -> Option<u64> {
+        let mut value = 0;
+        for _ in 0..bit_count {
+            value = (value << 1) | self.read_bit()? as u64;
+        }
+        Some(value)
+    }
+
+    pub(crate) fn read_rice(&mut self, parameter: u8) -> Option<u64> {
+        let mut quotient = 0u64;
+        while self.read_bit()? {
+            quotient += 1;
+        }
+
+        Some((quotient << parameter) | self.read_bits(parameter)?)
+    }
+
+    /// Whether only zero padding bits of the last byte are left.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        let byte_position = self.position.div_ceil(8);
+        byte_position == self.bytes.len()
+            && (self.position % 8 == 0 || self.bytes[self.position / 8] << (self.position % 8) == 0)
+    }
+}
+
+/// Picks the Rice parameter for geometrically distributed values with the given mean.
+pub(crate) fn rice_parameter(mean: f64) -> u8 {
+    if mean * std::f64::consts::LN_2 < 1. {
+        0
+    } else {
+        (mean * std::f64::consts::LN_2).log2().floor() as u8
+    }
+}
+
+/// Encodes the gaps between the set bins, and the gap after the last set bin, with a
+/// Golomb-Rice code. The bin count is followed by the number of set bins as a `u64` and the Rice
+/// parameter as a single byte.
+pub fn golomb_rice_compress(bins: &[bool]) -> Vec<u8> {
+    let one_count = bins.iter().filter(|b| **b).count();
+    let parameter = rice_parameter((bins.len() - one_count) as f64 / (one_count + 1) as f64);
+
+    let mut writer = BitWriter::new();
+    let mut next_position = 0;
+    for (position, _) in bins.iter().enumerate().filter(|(_, b)| **b) {
+        writer.write_rice((position - next_position) as u64, parameter);
+        next_position = position + 1;
+    }
+    writer.write_rice((bins.len() - next_position) as u64, parameter);
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(bins.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(one_count as u64).to_le_bytes());
+    bytes.push(parameter);
+    bytes.extend(writer.into_bytes());
+
+    bytes
+}
+
+pub fn golomb_rice_decompress(bytes: &[u8]) -> Result<Vec<bool>, DecodeError> {
+    if bytes.len() < 17 {
+        return Err(DecodeError::Truncated);
+    }
+
+    let bin_count = read_usize(&bytes[0..8])?;
+    let one_count = read_usize(&bytes[8..16])?;
+    let parameter = bytes[16];
+    if one_count > bin_count || parameter >= 64 {
+        return Err(DecodeError::InvalidParameters);
+    }
+
+    // Every code takes at least one bit plus one bit per 2^parameter zeros, so a header that
+    // claims more bins than the codes can hold is rejected before allocating them
+    let code_bits = (bytes.len() - 17) as u128 * 8;
+    if one_count as u128 >= code_bits || (bin_count - one_count) as u128 >= (code_bits << parameter)
+    {
+        return Err(DecodeError::InvalidParameters);
+    }
+
+    let mut bins = vec![false; bin_count];
+    let mut reader = BitReader::new(&bytes[17..]);
+    let mut next_position = 0usize;
+    for _ in 0..one_count {
+        let gap = reader.read_rice(parameter).ok_or(DecodeError::Truncated)?;
+        let position = usize::try_from(gap)
+            .ok()
+            .and_then(|gap| next_position.checked_add(gap))
+            .filter(|position| *position < bin_count)
+            .ok_or(DecodeError::InvalidParameters)?;
+
+        bins[position] = true;
+        next_position = position + 1;
+    }
+
+    let last_gap = reader.read_rice(parameter).ok_or(DecodeError::Truncated)?;
+    if last_gap != (bin_count - next_position) as u64 {
+        return Err(DecodeError::InvalidParameters);
+    }
+
+    if !reader.is_exhausted() {
+        return Err(DecodeError::TrailingData);
+    }
+
+    Ok(bins)
+}
+
+const ANS_PROBABILITY_BITS: u32 = 12;
+const ANS_PROBABILITY_SCALE: u32 = 1 << ANS_PROBABILITY_BITS;
+const ANS_LOWER_BOUND: u32 = 1 << 23;
+
+/// Encodes the bins with a range asymmetric numeral system (rANS) coder, modelling every bin as
+/// an independent coin flip with the filter's fill ratio. The bin count is followed by the
+/// quantized probability of a set bin as a `u16`.
+pub fn ans_compress(bins: &[bool]) -> Vec<u8> {
+    let one_count = bins.iter().filter(|b| **b).count();
+    let one_frequency = if bins.is_empty() {
+        1
+    } else {
+        ((one_count as f64 / bins.len() as f64) * ANS_PROBABILITY_SCALE as f64).round() as u32
+    }
+    .clamp(1, ANS_PROBABILITY_SCALE - 1);
+    let zero_frequency = ANS_PROBABILITY_SCALE - one_frequency;
+
+    // rANS works like a stack, so the bins are encoded back to front and the output reversed
+    let mut reversed = Vec::new();
+    let mut state = ANS_LOWER_BOUND;
+    for bin in bins.iter().rev() {
+        let (start, frequency) = if *bin {
+            (zero_frequency, one_frequency)
+        } else {
+            (0, zero_frequency)
+        };
+
+        let state_max = ((ANS_LOWER_BOUND >> ANS_PROBABILITY_BITS) << 8) * frequency;
+        while state >= state_max {
+            reversed.push(state as u8);
+            state >>= 8;
+        }
+
+        state = ((state / frequency) << ANS_PROBABILITY_BITS) + state % frequency + start;
+    }
+    reversed.extend_from_slice(&state.to_le_bytes());
+
+    let mut bytes = Vec::with_capacity(10 + reversed.len());
+    bytes.extend_from_slice(&(bins.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(one_frequency as u16).to_le_bytes());
+    bytes.extend(reversed.into_iter().rev());
+
+    bytes
+}
+
+pub fn ans_decompress(bytes: &[u8]) -> Result<Vec<bool>, DecodeError> {
+    if bytes.len() < 14 {
+        return Err(DecodeError::Truncated);
+    }
+
+    let bin_count = read_usize(&bytes[0..8])?;
+    let one_frequency = u16::from_le_bytes(bytes[8..10].try_into().unwrap()) as u32;
+    if one_frequency == 0 || one_frequency >= ANS_PROBABILITY_SCALE {
+        return Err(DecodeError::InvalidParameters);
+    }
+
+    // Every bin takes more than 1/ANS_PROBABILITY_SCALE of a bit of the state and the stream
+    if bin_count as u128 > (bytes.len() - 10) as u128 * 8 * ANS_PROBABILITY_SCALE as u128 {
+        return Err(DecodeError::InvalidParameters);
+    }
+    let zero_frequency = ANS_PROBABILITY_SCALE - one_frequency;
+
+    let mut state = u32::from_be_bytes(bytes[10..14].try_into().unwrap());
+    let mut stream = bytes[14..].iter();
+    let mut bins = Vec::new();
+    for _ in 0..bin_count {
+        let slot = state & (ANS_PROBABILITY_SCALE - 1);
+        let bin = slot >= zero_frequency;
+        let (start, frequency) = if bin {
+            (zero_frequency, one_frequency)
+        } else {
+            (0, zero_frequency)
+        };
+
+        state = frequency * (state >> ANS_PROBABILITY_BITS) + slot - start;
+        while state < ANS_LOWER_BOUND {
+            state = (state << 8) | *stream.next().ok_or(DecodeError::Truncated)? as u32;
+        }
+
+        bins.push(bin);
+    }
+
+    if stream.next().is_some() || state != ANS_LOWER_BOUND {
+        return Err(DecodeError::TrailingData);
+    }
+
+    Ok(bins)
+}
+
+fn read_usize(bytes: &[u8]) -> Result<usize, DecodeError> {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+        .try_into()
+        .map_err(|_| DecodeError::InvalidParameters)
+}
+
+/// For a maximum error rate and maximum set size, returns the bin count and hash count that
+/// minimize the size of the compressed filter (its bin count times the entropy of its fill
+/// ratio), while keeping the uncompressed filter at most `max_bin_count` bins. Returns `None` if
+/// even the smallest uncompressed filter needs more bins.
+pub fn gen_compressed_bloom_filter_params(
+    max_error_rate: f64,
+    max_set_size: usize,
+    max_bin_count: usize,
+) -> Option<(usize, usize)> {
+    // Beyond the hash count that minimizes the uncompressed size, both the bin count and the
+    // entropy per bin only grow
+    let (_, max_hash_count) = gen_bloom_filter_params(max_error_rate, max_set_size);
+
+    (1..=max_hash_count)
+        .map(|h| (bloom_filter_bin_count(max_error_rate, max_set_size, h), h))
+        .filter(|(bin_count, _)| *bin_count <= max_bin_count)
+        .min_by(|a, b| {
+            compressed_size(a.0, a.1, max_set_size)
+                .partial_cmp(&compressed_size(b.0, b.1, max_set_size))
+                .unwrap()
+        })
+}
+
+/// The expected size in bits of an optimally compressed filter. If the fill ratio is too close
+/// to 0 or 1 to compute its entropy, this is the uncompressed size, so that such a filter never
+/// looks free.
+fn compressed_size(bin_count: usize, hash_count: usize, set_size: usize) -> f64 {
+    let fill_ratio = -(-(hash_count as f64) * set_size as f64 / bin_count as f64).exp_m1();
+    if fill_ratio <= 0. || fill_ratio >= 1. {
+        return bin_count as f64;
+    }
+
+    let entropy = -fill_ratio * fill_ratio.log2() - (1. - fill_ratio) * (1. - fill_ratio).log2();
+    bin_count as f64 * entropy
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{gen_bloom_filter_params, Xxh3Hasher};
+    use crate::compression::{
+        ans_compress, ans_decompress, gen_compressed_bloom_filter_params, golomb_rice_compress,
+        golomb_rice_decompress,
+    };
+    use crate::sets::Set;
+    use crate::wire_format::DecodeError;
+
+    #[test]
+    fn test_golomb_rice_round_trip() {
+        let set = Set::random(100, 10000);
+        let bloom_filter = set.to_bloom_filter::<Xxh3Hasher>(10000, 2);
+        let compressed = golomb_rice_compress(&bloom_filter);
+
+        assert!(compressed.len() < bloom_filter.len() / 8);
+        assert_eq!(golomb_rice_decompress(&compressed).unwrap(), bloom_filter);
+    }
+
+    #[test]
+    fn test_golomb_rice_edge_cases() {
+        for bins in [
+            vec![],
+            vec![false; 9],
+            vec![true; 9],
+            vec![true, false, true],
+        ] {
+            assert_eq!(
+                golomb_rice_decompress(&golomb_rice_compress(&bins)).unwrap(),
+                bins
+            );
+        }
+    }
+
+    #[test]
+    fn test_golomb_rice_malformed() {
+        let compressed = golomb_rice_compress(&[false, true, false, true]);
+        assert_eq!(
+            golomb_rice_decompress(&compressed[..16]),
+            Err(DecodeError::Truncated)
+        );
+
+        let mut trailing = compressed.clone();
+        trailing.push(0xff);
+        assert_eq!(
+            golomb_rice_decompress(&trailing),
+            Err(DecodeError::TrailingData)
+        );
+
+        // A header that claims far more bins than the codes can describe
+        let mut huge = compressed;
+        huge[0..8].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        assert_eq!(
+            golomb_rice_decompress(&huge),
+            Err(DecodeError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_ans_round_trip() {
+        let set = Set::random(100, 10000);
+        let bloom_filter = set.to_bloom_filter::<Xxh3Hasher>(10000, 2);
+        let compressed = ans_compress(&bloom_filter);
+
+        assert!(compressed.len() < bloom_filter.len() / 8);
+        assert_eq!(ans_decompress(&compressed).unwrap(), bloom_filter);
+    }
+
+    #[test]
+    fn test_ans_edge_cases() {
+        for bins in [
+            vec![],
+            vec![false; 9],
+            vec![true; 9],
+            vec![true, false, true],
+        ] {
+            assert_eq!(ans_decompress(&ans_compress(&bins)).unwrap(), bins);
+        }
+    }
+
+    #[test]
+    fn test_ans_malformed() {
+        let compressed = ans_compress(&[false, true, false, true]);
+        assert_eq!(
+            ans_decompress(&compressed[..12]),
+            Err(DecodeError::Truncated)
+        );
+
+        let mut trailing = compressed.clone();
+        trailing.push(0);
+        assert_eq!(ans_decompress(&trailing), Err(DecodeError::TrailingData));
+
+        let mut huge = compressed;
+        huge[0..8].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        assert_eq!(ans_decompress(&huge), Err(DecodeError::InvalidParameters));
+    }
+
+    #[test]
+    fn test_compressed_params() {
+        let (bin_count, hash_count) = gen_bloom_filter_params(2f64.powf(-10.), 1000);
+        let (compressed_bin_count, compressed_hash_count) =
+            gen_compressed_bloom_filter_params(2f64.powf(-10.), 1000, 4 * bin_count).unwrap();
+
+        assert!(compressed_bin_count > bin_count);
+        assert!(compressed_bin_count <= 4 * bin_count);
+        assert!(compressed_hash_count < hash_count);
+
+        assert_eq!(
+            gen_compressed_bloom_filter_params(2f64.powf(-10.), 1000, bin_count),
+            Some((bin_count, hash_count))
+        );
+        assert_eq!(
+            gen_compressed_bloom_filter_params(2f64.powf(-10.), 1000, bin_count - 1),
+            None
+        );
+    }
+}
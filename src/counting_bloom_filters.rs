@@ -0,0 +1,167 @@
+//! Counting Bloom filters, which keep a counter instead of a bit per bin so that elements can be
+//! removed again. They use the same indices as `bloom_filter_indices`, so the bins of the
+//! equivalent Bloom filter are the counters that are not zero.
+use crate::bloom_filters::{bloom_filter_indices, ElementHasher};
+use crate::sets::Set;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CountingBloomFilter {
+    pub counters: Vec<usize>,
+    pub hash_count: usize,
+    pub hasher_id: u8,
+}
+
+impl CountingBloomFilter {
+    pub fn new<H: ElementHasher>(bin_count: usize, hash_count: usize) -> Self {
+        CountingBloomFilter {
+            counters: vec![0; bin_count],
+            hash_count,
+            hasher_id: H::ID,
+        }
+    }
+
+    pub fn from_set<H: ElementHasher>(set: &Set, bin_count: usize, hash_count: usize) -> Self {
+        let mut filter = CountingBloomFilter::new::<H>(bin_count, hash_count);
+        for element in &set.elements {
+            filter.insert::<H>(element);
+        }
+
+        filter
+    }
+
+    pub fn bin_count(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// The indices of the element's bins, an index occurs once per hash that maps to it.
+    fn indices<H: ElementHasher>(&self, element: &usize) -> Vec<usize> {
+        assert_eq!(H::ID, self.hasher_id);
+
+        bloom_filter_indices::<H>(element, self.counters.len(), self.hash_count).collect()
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn insert<H: ElementHasher>(&mut self, element: &usize) {
+        for index in self.indices::<H>(element) {
+            self.counters[index] += 1;
+        }
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        // An index can occur more than once, in which case its counter must cover every occurrence
+        let mut indices = self.indices::<H>(element);
+        indices.sort_unstable();
+        indices
+            .chunk_by(|a, b| a == b)
+            .all(|occurrences| self.counters[occurrences[0]] >= occurrences.len())
+    }
+
+    /// Removes an element that was inserted before and returns whether it was removed. Returns
+    /// false and leaves the counters unchanged if the filter does not contain the element.
+    /// Removing an element that was never inserted but is a false positive corrupts the filter.
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn remove<H: ElementHasher>(&mut self, element: &usize) -> bool {
+        if !self.contains::<H>(element) {
+            return false;
+        }
+
+        for index in self.indices::<H>(element) {
+            self.counters[index] -= 1;
+        }
+
+        true
+    }
+
+    /// The bins of the Bloom filter with the same elements, as built by `Set::to_bloom_filter`.
+    pub fn to_bins(&self) -> Vec<bool> {
+        self.counters.iter().map(|counter| *counter > 0).collect()
+    }
+}
+
+impl Set {
+    pub fn to_counting_bloom_filter<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+    ) -> CountingBloomFilter {
+        CountingBloomFilter::from_set::<H>(self, bin_count, hash_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{bloom_filter_contains, Blake3Hasher, Xxh3Hasher};
+    use crate::counting_bloom_filters::CountingBloomFilter;
+    use crate::sets::Set;
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_matches_bloom_filter() {
+        let set = Set::random(100, 10_000);
+        let filter = set.to_counting_bloom_filter::<H>(1000, 3);
+
+        assert_eq!(filter.to_bins(), set.to_bloom_filter::<H>(1000, 3));
+        assert_eq!(filter.bin_count(), 1000);
+    }
+
+    #[test]
+    fn test_remove() {
+        let set = Set::new(&[1, 3, 4]);
+        let mut filter = set.to_counting_bloom_filter::<H>(50, 3);
+
+        assert!(filter.remove::<H>(&3));
+        assert!(!filter.contains::<H>(&3));
+        assert!(!filter.remove::<H>(&3));
+
+        filter.insert::<H>(&3);
+        assert!(filter.contains::<H>(&3));
+    }
+
+    #[test]
+    fn test_remove_keeps_remaining_elements() {
+        // A small filter, so that many bins are shared between elements
+        let set = Set::random(500, 1_000_000);
+        let mut filter = set.to_counting_bloom_filter::<H>(1000, 4);
+
+        let elements: Vec<usize> = set.elements.iter().copied().collect();
+        let (removed, remaining) = elements.split_at(250);
+        for element in removed {
+            assert!(filter.remove::<H>(element));
+        }
+
+        let bins = filter.to_bins();
+        for element in remaining {
+            assert!(filter.contains::<H>(element));
+            assert!(bloom_filter_contains::<H>(&bins, element, 4));
+        }
+
+        for element in remaining {
+            assert!(filter.remove::<H>(element));
+        }
+        assert!(filter.counters.iter().all(|counter| *counter == 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_hasher() {
+        let mut filter = CountingBloomFilter::new::<H>(100, 3);
+        filter.insert::<Blake3Hasher>(&3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let filter = Set::new(&[1, 3, 4]).to_counting_bloom_filter::<H>(50, 3);
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CountingBloomFilter>(&json).unwrap(),
+            filter
+        );
+    }
+}
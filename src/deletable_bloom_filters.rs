@@ -0,0 +1,192 @@
+//! Deletable Bloom filters (Rothenberg et al.), which support removal at the cost of a small
+//! bitmap instead of counters.
+//!
+//! The bins are split into `region_count` equal regions. Whenever an insertion sets a bin that
+//! was already set, the region of that bin is marked as collided. An element can be removed if
+//! at least one of its bins lies in a collision-free region, by resetting its bins in the
+//! collision-free regions only. Bins in collided regions may be shared with other elements and
+//! are never reset, so removing an inserted element never introduces false negatives. Removing an
+//! element that was never inserted but is a false positive may reset bins of inserted elements,
+//! so only elements that were inserted may be removed.
+//!
+//! The bins use the same indices as `bloom_filter_indices`, so they can be queried like the
+//! filter built by `Set::to_bloom_filter`.
+use crate::bloom_filters::{bloom_filter_contains, bloom_filter_indices, ElementHasher};
+use crate::sets::Set;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeletableBloomFilter {
+    pub bins: Vec<bool>,
+    pub hash_count: usize,
+    /// One bit per region, set if two insertions set the same bin in that region.
+    pub collisions: Vec<bool>,
+    pub hasher_id: u8,
+}
+
+impl DeletableBloomFilter {
+    /// Panics if `region_count` is zero or larger than `bin_count`.
+    pub fn new<H: ElementHasher>(bin_count: usize, hash_count: usize, region_count: usize) -> Self {
+        assert!(region_count > 0 && region_count <= bin_count);
+
+        DeletableBloomFilter {
+            bins: vec![false; bin_count],
+            hash_count,
+            collisions: vec![false; region_count],
+            hasher_id: H::ID,
+        }
+    }
+
+    pub fn from_set<H: ElementHasher>(
+        set: &Set,
+        bin_count: usize,
+        hash_count: usize,
+        region_count: usize,
+    ) -> Self {
+        let mut filter = DeletableBloomFilter::new::<H>(bin_count, hash_count, region_count);
+        for element in &set.elements {
+            filter.insert::<H>(element);
+        }
+
+        filter
+    }
+
+    pub fn bin_count(&self) -> usize {
+        self.bins.len()
+    }
+
+    pub fn region_count(&self) -> usize {
+        self.collisions.len()
+    }
+
+    fn region(&self, index: usize) -> usize {
+        index * self.collisions.len() / self.bins.len()
+    }
+
+    /// The distinct indices of the element's bins.
+    fn indices<H: ElementHasher>(&self, element: &usize) -> Vec<usize> {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let mut indices: Vec<usize> =
+            bloom_filter_indices::<H>(element, self.bins.len(), self.hash_count).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn insert<H: ElementHasher>(&mut self, element: &usize) {
+        for index in self.indices::<H>(element) {
+            if self.bins[index] {
+                let region = self.region(index);
+                self.collisions[region] = true;
+            }
+            self.bins[index] = true;
+        }
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        assert_eq!(H::ID, self.hasher_id);
+
+        bloom_filter_contains::<H>(&self.bins, element, self.hash_count)
+    }
+
+    /// Whether the element is contained and has a bin in a collision-free region.
+    pub fn is_deletable<H: ElementHasher>(&self, element: &usize) -> bool {
+        self.contains::<H>(element)
+            && self
+                .indices::<H>(element)
+                .into_iter()
+                .any(|index| !self.collisions[self.region(index)])
+    }
+
+    /// Removes an element that was inserted before and returns whether it was removed. Returns
+    /// false and leaves the filter unchanged if the element is not contained or all of its bins
+    /// lie in collided regions. Removing an element that was never inserted may introduce false
+    /// negatives. Panics if `H` is not the hasher that built this filter.
+    pub fn remove<H: ElementHasher>(&mut self, element: &usize) -> bool {
+        if !self.is_deletable::<H>(element) {
+            return false;
+        }
+
+        for index in self.indices::<H>(element) {
+            if !self.collisions[self.region(index)] {
+                self.bins[index] = false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{Blake3Hasher, Xxh3Hasher};
+    use crate::deletable_bloom_filters::DeletableBloomFilter;
+    use crate::sets::Set;
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_matches_bloom_filter() {
+        let set = Set::random(100, 10_000);
+        let filter = DeletableBloomFilter::from_set::<H>(&set, 1000, 3, 100);
+
+        assert_eq!(filter.bins, set.to_bloom_filter::<H>(1000, 3));
+        assert_eq!(filter.region_count(), 100);
+    }
+
+    #[test]
+    fn test_remove() {
+        let set = Set::new(&[1, 3, 4]);
+        let mut filter = DeletableBloomFilter::from_set::<H>(&set, 1000, 3, 1000);
+
+        assert!(filter.remove::<H>(&3));
+        assert!(!filter.contains::<H>(&3));
+        assert!(!filter.remove::<H>(&3));
+        assert!(filter.contains::<H>(&1));
+        assert!(filter.contains::<H>(&4));
+    }
+
+    #[test]
+    fn test_remove_keeps_remaining_elements() {
+        // A small filter, so that many regions are collided
+        let set = Set::random(300, 1_000_000);
+        let mut filter = DeletableBloomFilter::from_set::<H>(&set, 2000, 4, 200);
+        assert!(filter.collisions.iter().any(|collided| *collided));
+
+        let elements: Vec<usize> = set.elements.iter().copied().collect();
+        let (removed, remaining) = elements.split_at(150);
+        let removed_count = removed
+            .iter()
+            .filter(|element| filter.remove::<H>(element))
+            .count();
+        assert!(removed_count > 0);
+
+        for element in remaining {
+            assert!(filter.contains::<H>(element));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_hasher() {
+        let mut filter = DeletableBloomFilter::new::<H>(100, 3, 10);
+        filter.insert::<Blake3Hasher>(&3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let filter = DeletableBloomFilter::from_set::<H>(&Set::new(&[1, 3, 4]), 50, 3, 5);
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DeletableBloomFilter>(&json).unwrap(),
+            filter
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! Generators that draw elements and multiplicities from non-uniform distributions.
+//!
+//! Elements are sampled without replacement, with probabilities proportional to the weights of
+//! the distribution, so the generators keep the exact size guarantees of their uniform
+//! counterparts. They take time and memory linear in the universe.
+use crate::error::Error;
+use crate::multisets::Multiset;
+use crate::sets::{
+    check_intersection_parameters, check_union_parameters, fill_around_intersection, Set,
+};
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution as _;
+use rand::rngs::OsRng;
+use rand::seq::index::sample_weighted;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::cmp;
+
+/// A distribution over the values `0, 1, 2, ...`, given by unnormalized weights. For elements the
+/// value is the element itself, for multiplicities value `i` stands for the count `i + 1`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Distribution {
+    Uniform,
+    /// Weight `1 / (i + 1)^exponent`.
+    Zipf {
+        exponent: f64,
+    },
+    /// Weight `(1 - p)^i`.
+    Geometric {
+        p: f64,
+    },
+    /// Weight `lambda^i / i!`.
+    Poisson {
+        lambda: f64,
+    },
+    /// Weight `histogram[i]`, values beyond the histogram have weight 0.
+    Empirical(Vec<f64>),
+}
+
+impl Distribution {
+    pub fn weight(&self, value: usize) -> f64 {
+        match self {
+            Distribution::Uniform => 1.,
+            Distribution::Zipf { exponent } => (value as f64 + 1.).powf(-exponent),
+            Distribution::Geometric { p } => (1. - p).powf(value as f64),
+            Distribution::Poisson { lambda } => {
+                (value as f64 * lambda.ln() - lambda - ln_factorial(value)).exp()
+            }
+            Distribution::Empirical(histogram) => histogram.get(value).copied().unwrap_or(0.),
+        }
+    }
+
+    /// Samples `amount` distinct values from `candidates`. Returns an error if a weight is negative
+    /// or NaN, or if fewer than `amount` candidates have a positive weight.
+    fn sample_from(&self, candidates: &[usize], amount: usize) -> Result<Vec<usize>, Error> {
+        if amount > candidates.len() {
+            return Err(Error::Infeasible(
+                "the element count exceeds the number of candidates",
+            ));
+        }
+
+        let indices = sample_weighted(
+            &mut OsRng,
+            candidates.len(),
+            |i| self.weight(candidates[i]),
+            amount,
+        )
+        .map_err(|_| Error::InvalidDistribution)?;
+
+        // Candidates without weight are only drawn once the others are exhausted
+        let values: Vec<usize> = indices.into_iter().map(|i| candidates[i]).collect();
+        if values.iter().any(|value| self.weight(*value) <= 0.) {
+            return Err(Error::InvalidDistribution);
+        }
+
+        Ok(values)
+    }
+}
+
+/// Stirling's series, exact for small values.
+fn ln_factorial(value: usize) -> f64 {
+    if value < 16 {
+        return (2..=value).map(|i| (i as f64).ln()).sum();
+    }
+
+    let n = value as f64;
+    n * n.ln() - n + 0.5 * (2. * std::f64::consts::PI * n).ln() + 1. / (12. * n)
+        - 1. / (360. * n.powi(3))
+}
+
+impl Set {
+    /// Panics if `element_count` exceeds `universe` or the distribution is invalid, see
+    /// `Set::try_random_from_distribution`.
+    pub fn random_from_distribution(
+        element_count: usize,
+        universe: usize,
+        distribution: &Distribution,
+    ) -> Self {
+        Set::try_random_from_distribution(element_count, universe, distribution)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Returns an error if `element_count` exceeds `universe`, or if the distribution has
+    /// invalid weights or fewer than `element_count` elements with a positive weight.
+    pub fn try_random_from_distribution(
+        element_count: usize,
+        universe: usize,
+        distribution: &Distribution,
+    ) -> Result<Self, Error> {
+        let candidates: Vec<usize> = (0..universe).collect();
+        Ok(distribution
+            .sample_from(&candidates, element_count)?
+            .into_iter()
+            .collect())
+    }
+}
+
+impl Multiset {
+    /// Like `Multiset::random`, but draws the elements from `elements` and the counts from
+    /// `multiplicities`, truncated to `1..=max_multiplicity`. Panics on invalid parameters, see
+    /// `Multiset::try_random_from_distributions`.
+    pub fn random_from_distributions(
+        element_count: usize,
+        universe: usize,
+        max_multiplicity: usize,
+        elements: &Distribution,
+        multiplicities: &Distribution,
+    ) -> Self {
+        Multiset::try_random_from_distributions(
+            element_count,
+            universe,
+            max_multiplicity,
+            elements,
+            multiplicities,
+        )
+        .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_random_from_distributions(
+        element_count: usize,
+        universe: usize,
+        max_multiplicity: usize,
+        elements: &Distribution,
+        multiplicities: &Distribution,
+    ) -> Result<Self, Error> {
+        if max_multiplicity == 0 {
+            return Err(Error::InvalidParameter(
+                "the maximum multiplicity must be at least 1",
+            ));
+        }
+
+        let counts = WeightedIndex::new((0..max_multiplicity).map(|i| multiplicities.weight(i)))
+            .map_err(|_| Error::InvalidDistribution)?;
+
+        Ok(
+            Set::try_random_from_distribution(element_count, universe, elements)?
+                .elements
+                .into_iter()
+                .map(|element| (element, counts.sample(&mut OsRng) + 1))
+                .collect(),
+        )
+    }
+}
+
+/// Same as `gen_sets_with_intersection`, but draws the elements from `distribution`.
+pub fn gen_sets_with_intersection_from_distribution(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    intersection_size: usize,
+    distribution: &Distribution,
+) -> Vec<Set> {
+    try_gen_sets_with_intersection_from_distribution(
+        set_count,
+        element_count,
+        universe,
+        intersection_size,
+        distribution,
+    )
+    .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Same as `try_gen_sets_with_intersection`, but draws the elements from `distribution`.
+pub fn try_gen_sets_with_intersection_from_distribution(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    intersection_size: usize,
+    distribution: &Distribution,
+) -> Result<Vec<Set>, Error> {
+    check_intersection_parameters(set_count, element_count, universe, intersection_size)?;
+
+    let intersection =
+        Set::try_random_from_distribution(intersection_size, universe, distribution)?;
+
+    // Elements without weight cannot be drawn, so they do not count towards the universe
+    let candidates: Vec<usize> = (0..universe)
+        .filter(|element| !intersection.contains(element) && distribution.weight(*element) != 0.)
+        .collect();
+    if set_count * (element_count - intersection_size) > (set_count - 1) * candidates.len() {
+        return Err(Error::Infeasible(
+            "the distribution has too few elements to keep them out of the intersection",
+        ));
+    }
+
+    let pool_size = cmp::min(
+        candidates.len(),
+        set_count * (element_count - intersection_size),
+    );
+    let pool = distribution.sample_from(&candidates, pool_size)?;
+
+    Ok(fill_around_intersection(
+        &mut OsRng,
+        set_count,
+        element_count,
+        &intersection,
+        &pool,
+    ))
+}
+
+/// Same as `gen_sets_with_union`, but draws the elements from `distribution`.
+pub fn gen_sets_with_union_from_distribution(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    union_size: usize,
+    distribution: &Distribution,
+) -> Vec<Set> {
+    try_gen_sets_with_union_from_distribution(
+        set_count,
+        element_count,
+        universe,
+        union_size,
+        distribution,
+    )
+    .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Same as `try_gen_sets_with_union`, but draws the elements from `distribution`.
+pub fn try_gen_sets_with_union_from_distribution(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    union_size: usize,
+    distribution: &Distribution,
+) -> Result<Vec<Set>, Error> {
+    check_union_parameters(set_count, element_count, universe, union_size)?;
+
+    let union = Set::try_random_from_distribution(union_size, universe, distribution)?;
+
+    let mut sets = vec![Set::new(&[]); set_count];
+
+    // Distribute elements randomly
+    let mut union_elements: Vec<usize> = union.elements.iter().copied().collect();
+    union_elements.shuffle(&mut OsRng);
+    for element in &union_elements {
+        loop {
+            let index = OsRng.gen_range(0..set_count);
+            if sets[index].len() < element_count {
+                sets[index].elements.insert(*element);
+                break;
+            }
+        }
+    }
+
+    // Fill with other elements of the union
+    for set in sets.iter_mut() {
+        let candidates: Vec<usize> = union_elements
+            .iter()
+            .copied()
+            .filter(|element| !set.contains(element))
+            .collect();
+
+        let amount = element_count - set.len();
+        set.elements
+            .extend(distribution.sample_from(&candidates, amount)?);
+    }
+
+    Ok(sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::distributions::{
+        gen_sets_with_intersection_from_distribution, gen_sets_with_union_from_distribution,
+        try_gen_sets_with_intersection_from_distribution, Distribution,
+    };
+    use crate::error::Error;
+    use crate::multisets::Multiset;
+    use crate::sets::Set;
+
+    #[test]
+    fn test_weights() {
+        assert_eq!(Distribution::Uniform.weight(7), 1.);
+        assert_eq!(Distribution::Zipf { exponent: 2. }.weight(1), 0.25);
+        assert_eq!(Distribution::Geometric { p: 0.5 }.weight(2), 0.25);
+        assert!(
+            (Distribution::Poisson { lambda: 2. }.weight(2) - 2. * (-2f64).exp()).abs() < 1e-12
+        );
+        assert!((Distribution::Poisson { lambda: 20. }.weight(20) - 0.0888353).abs() < 1e-6);
+        assert_eq!(Distribution::Empirical(vec![1., 3.]).weight(1), 3.);
+        assert_eq!(Distribution::Empirical(vec![1., 3.]).weight(2), 0.);
+    }
+
+    #[test]
+    fn test_random_zipf() {
+        let set = Set::random_from_distribution(100, 10_000, &Distribution::Zipf { exponent: 1.5 });
+
+        assert_eq!(set.len(), 100);
+        assert!(set.elements.iter().all(|element| *element < 10_000));
+        assert!(
+            set.elements
+                .iter()
+                .filter(|element| **element < 1000)
+                .count()
+                > 80
+        );
+    }
+
+    #[test]
+    fn test_random_empirical() {
+        let set =
+            Set::random_from_distribution(3, 100, &Distribution::Empirical(vec![1., 0., 1., 1.]));
+        assert_eq!(set, Set::new(&[0, 2, 3]));
+    }
+
+    #[test]
+    fn test_multiset_random_geometric() {
+        let multiset = Multiset::random_from_distributions(
+            1000,
+            100_000,
+            10,
+            &Distribution::Uniform,
+            &Distribution::Geometric { p: 0.5 },
+        );
+
+        assert_eq!(multiset.len(), 1000);
+        for count in multiset.element_counts.values() {
+            assert!(*count >= 1 && *count <= 10);
+        }
+        let ones = multiset
+            .element_counts
+            .values()
+            .filter(|count| **count == 1)
+            .count();
+        assert!(ones > 400 && ones < 600);
+    }
+
+    #[test]
+    fn test_gen_sets_with_intersection() {
+        let sets = gen_sets_with_intersection_from_distribution(
+            3,
+            10,
+            100,
+            4,
+            &Distribution::Poisson { lambda: 30. },
+        );
+
+        assert_eq!(Set::intersection(&sets).len(), 4);
+        for set in &sets {
+            assert_eq!(set.len(), 10);
+        }
+    }
+
+    #[test]
+    fn test_gen_sets_with_union() {
+        let sets = gen_sets_with_union_from_distribution(
+            3,
+            10,
+            100,
+            20,
+            &Distribution::Zipf { exponent: 1. },
+        );
+
+        assert_eq!(Set::union(&sets).len(), 20);
+        for set in &sets {
+            assert_eq!(set.len(), 10);
+        }
+    }
+
+    #[test]
+    fn test_invalid_distributions() {
+        assert_eq!(
+            Set::try_random_from_distribution(3, 100, &Distribution::Empirical(vec![1., 0., 1.])),
+            Err(Error::InvalidDistribution)
+        );
+        assert_eq!(
+            Set::try_random_from_distribution(3, 100, &Distribution::Empirical(vec![1., -1., 1.])),
+            Err(Error::InvalidDistribution)
+        );
+        assert_eq!(
+            Multiset::try_random_from_distributions(
+                3,
+                100,
+                2,
+                &Distribution::Uniform,
+                &Distribution::Empirical(vec![0.])
+            ),
+            Err(Error::InvalidDistribution)
+        );
+
+        // Only 6 elements outside the intersection can be drawn, each in at most 2 of the sets,
+        // while 3 sets of 9 elements need 15 occurrences
+        assert!(matches!(
+            try_gen_sets_with_intersection_from_distribution(
+                3,
+                9,
+                100,
+                4,
+                &Distribution::Empirical(vec![1.; 10])
+            ),
+            Err(Error::Infeasible(_))
+        ));
+    }
+}
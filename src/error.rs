@@ -0,0 +1,31 @@
+use std::fmt;
+
+/// The error returned by the `try_` variants of the generators and n-ary operations.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Error {
+    /// An operation on several sets or sketches received fewer than it needs.
+    NotEnoughSets { required: usize, given: usize },
+    /// A parameter is out of its valid range.
+    InvalidParameter(&'static str),
+    /// The requested sizes cannot be realized within the universe.
+    Infeasible(&'static str),
+    /// A distribution has negative or NaN weights, or no positive weights where one is needed.
+    InvalidDistribution,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotEnoughSets { required, given } => write!(
+                f,
+                "the operation needs at least {} sets but {} were given",
+                required, given
+            ),
+            Error::InvalidParameter(reason) => write!(f, "invalid parameter: {}", reason),
+            Error::Infeasible(reason) => write!(f, "infeasible parameters: {}", reason),
+            Error::InvalidDistribution => write!(f, "the distribution has invalid weights"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
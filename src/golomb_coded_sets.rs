@@ -0,0 +1,328 @@
+use crate::bloom_filters::ElementHasher;
+use crate::compression::{BitReader, BitWriter};
+use crate::error::Error;
+use crate::sets::Set;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+/// A static approximate set: the hashes of the elements, mapped into `0..range()`, sorted and
+/// stored as Golomb-Rice coded differences. The false positive rate is about `2^-parameter`.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "GolombCodedSetFields")
+)]
+pub struct GolombCodedSet {
+    pub element_count: usize,
+    pub parameter: u8,
+    pub data: Vec<u8>,
+    pub hasher_id: u8,
+}
+
+/// A deserialized `GolombCodedSet` before its data is checked to hold `element_count` hashes
+/// below its range, and nothing else.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GolombCodedSetFields {
+    element_count: usize,
+    parameter: u8,
+    data: Vec<u8>,
+    hasher_id: u8,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GolombCodedSetFields> for GolombCodedSet {
+    type Error = Error;
+
+    fn try_from(fields: GolombCodedSetFields) -> Result<Self, Error> {
+        let range = if fields.parameter < 64 {
+            (fields.element_count as u64).checked_mul(1 << fields.parameter)
+        } else {
+            None
+        }
+        .ok_or(Error::InvalidParameter(
+            "the range of the Golomb-coded set exceeds 64 bits",
+        ))?;
+
+        let invalid_data = Error::InvalidParameter("the Golomb-coded set data is invalid");
+        let mut reader = BitReader::new(&fields.data);
+        let mut previous = 0u64;
+        for _ in 0..fields.element_count {
+            previous = reader
+                .read_rice(fields.parameter)
+                .and_then(|difference| previous.checked_add(difference))
+                .filter(|hash| *hash < range)
+                .ok_or_else(|| invalid_data.clone())?;
+        }
+        if !reader.is_exhausted() {
+            return Err(invalid_data);
+        }
+
+        Ok(GolombCodedSet {
+            element_count: fields.element_count,
+            parameter: fields.parameter,
+            data: fields.data,
+            hasher_id: fields.hasher_id,
+        })
+    }
+}
+
+impl Set {
+    /// Panics if `fp_rate` is not between 0 and 1 or is so small that the hashes do not fit in
+    /// 64 bits.
+    pub fn to_golomb_coded_set<H: ElementHasher>(&self, fp_rate: f64) -> GolombCodedSet {
+        self.try_to_golomb_coded_set::<H>(fp_rate)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_to_golomb_coded_set<H: ElementHasher>(
+        &self,
+        fp_rate: f64,
+    ) -> Result<GolombCodedSet, Error> {
+        if !(fp_rate > 0. && fp_rate < 1.) {
+            return Err(Error::InvalidParameter(
+                "the false-positive rate must be between 0 and 1",
+            ));
+        }
+
+        let parameter = (1. / fp_rate).log2().ceil();
+        let range = if parameter < 64. {
+            (self.len() as u64).checked_mul(1 << parameter as u8)
+        } else {
+            None
+        }
+        .ok_or(Error::InvalidParameter(
+            "the false-positive rate is too small for 64-bit hashes",
+        ))?;
+        let parameter = parameter as u8;
+
+        let mut hashes: Vec<u64> = self
+            .elements
+            .iter()
+            .map(|element| hash_to_range::<H>(element, range))
+            .collect();
+        hashes.sort_unstable();
+
+        let mut writer = BitWriter::new();
+        let mut previous = 0;
+        for hash in hashes {
+            writer.write_rice(hash - previous, parameter);
+            previous = hash;
+        }
+
+        Ok(GolombCodedSet {
+            element_count: self.len(),
+            parameter,
+            data: writer.into_bytes(),
+            hasher_id: H::ID,
+        })
+    }
+}
+
+impl GolombCodedSet {
+    pub fn range(&self) -> u64 {
+        (self.element_count as u64) << self.parameter
+    }
+
+    /// Decodes the hashes one by one, stopping as soon as they pass the hash of `element`.
+    /// Panics if `H` is not the hasher that built this set.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let target = hash_to_range::<H>(element, self.range());
+        for hash in self.hashes() {
+            if hash >= target {
+                return hash == target;
+            }
+        }
+
+        false
+    }
+
+    /// Queries many elements in a single pass over the coded hashes. The result is in the same
+    /// order as `elements`. Panics if `H` is not the hasher that built this set.
+    pub fn contains_all<H: ElementHasher>(&self, elements: &[usize]) -> Vec<bool> {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let range = self.range();
+        let mut targets: Vec<(u64, usize)> = elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| (hash_to_range::<H>(element, range), i))
+            .collect();
+        targets.sort_unstable();
+
+        let mut result = vec![false; elements.len()];
+        let mut hashes = self.hashes().peekable();
+        for (target, i) in targets {
+            while hashes.next_if(|hash| *hash < target).is_some() {}
+
+            result[i] = hashes.peek() == Some(&target);
+        }
+
+        result
+    }
+
+    fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        let mut reader = BitReader::new(&self.data);
+        let mut previous = 0;
+
+        (0..self.element_count).map(move |_| {
+            previous += reader
+                .read_rice(self.parameter)
+                .expect("the Golomb-coded set is truncated");
+            previous
+        })
+    }
+}
+
+/// Maps the element's hash uniformly into `0..range` using a multiply-shift.
+fn hash_to_range<H: ElementHasher>(element: &usize, range: u64) -> u64 {
+    ((H::hash_element(element, 0) as u64 as u128 * range as u128) >> 64) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{Blake3Hasher, Xxh3Hasher};
+    use crate::error::Error;
+    use crate::sets::Set;
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_contains() {
+        let set = Set::new(&[1, 3, 4, 100, 2000]);
+        let golomb_coded_set = set.to_golomb_coded_set::<H>(2f64.powf(-20.));
+
+        assert_eq!(golomb_coded_set.parameter, 20);
+        for element in &set.elements {
+            assert!(golomb_coded_set.contains::<H>(element));
+        }
+        assert!(!golomb_coded_set.contains::<H>(&2));
+        assert!(!golomb_coded_set.contains::<H>(&5));
+    }
+
+    #[test]
+    fn test_contains_all() {
+        let set = Set::random(1000, 1_000_000);
+        let golomb_coded_set = set.to_golomb_coded_set::<H>(2f64.powf(-10.));
+
+        let queries: Vec<usize> = (0..10_000).collect();
+        let results = golomb_coded_set.contains_all::<H>(&queries);
+
+        for (element, result) in queries.iter().zip(&results) {
+            assert_eq!(*result, golomb_coded_set.contains::<H>(element));
+            if set.contains(element) {
+                assert!(*result);
+            }
+        }
+
+        let false_positives = queries
+            .iter()
+            .zip(&results)
+            .filter(|(element, result)| **result && !set.contains(element))
+            .count();
+        assert!(false_positives < 100);
+    }
+
+    #[test]
+    fn test_smaller_than_bloom_filter() {
+        let set = Set::random(1000, 1_000_000);
+        let golomb_coded_set = set.to_golomb_coded_set::<H>(2f64.powf(-10.));
+
+        // A Bloom filter needs about 1.44 * 10 bits per element for this rate
+        assert!(golomb_coded_set.data.len() * 8 < 14 * 1000);
+    }
+
+    #[test]
+    fn test_empty() {
+        let golomb_coded_set = Set::new(&[]).to_golomb_coded_set::<H>(0.01);
+        assert!(!golomb_coded_set.contains::<H>(&1));
+        assert_eq!(
+            golomb_coded_set.contains_all::<H>(&[1, 2]),
+            vec![false, false]
+        );
+    }
+
+    #[test]
+    fn test_invalid_fp_rate() {
+        let set = Set::new(&[1, 3, 4]);
+        for fp_rate in [0., 1., 1.5, -0.1, f64::NAN] {
+            assert_eq!(
+                set.try_to_golomb_coded_set::<H>(fp_rate).unwrap_err(),
+                Error::InvalidParameter("the false-positive rate must be between 0 and 1")
+            );
+        }
+
+        let too_small =
+            Error::InvalidParameter("the false-positive rate is too small for 64-bit hashes");
+        assert_eq!(
+            set.try_to_golomb_coded_set::<H>(2f64.powf(-64.))
+                .unwrap_err(),
+            too_small
+        );
+        assert_eq!(
+            set.try_to_golomb_coded_set::<H>(1e-300).unwrap_err(),
+            too_small
+        );
+        assert_eq!(
+            set.try_to_golomb_coded_set::<H>(2f64.powf(-63.))
+                .unwrap_err(),
+            too_small
+        );
+        assert_eq!(
+            set.try_to_golomb_coded_set::<H>(2f64.powf(-60.))
+                .unwrap()
+                .parameter,
+            60
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_hasher() {
+        let golomb_coded_set = Set::new(&[1, 3, 4]).to_golomb_coded_set::<H>(0.01);
+        golomb_coded_set.contains::<Blake3Hasher>(&1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::golomb_coded_sets::GolombCodedSet;
+
+        let golomb_coded_set = Set::new(&[1, 3, 4, 100, 2000]).to_golomb_coded_set::<H>(0.01);
+        let json = serde_json::to_string(&golomb_coded_set).unwrap();
+        assert_eq!(
+            serde_json::from_str::<GolombCodedSet>(&json).unwrap(),
+            golomb_coded_set
+        );
+
+        // The data must hold exactly `element_count` hashes below the range
+        let invalid_sets = [
+            GolombCodedSet {
+                element_count: 6,
+                ..golomb_coded_set.clone()
+            },
+            GolombCodedSet {
+                element_count: 4,
+                ..golomb_coded_set.clone()
+            },
+            GolombCodedSet {
+                data: vec![0xff; 8],
+                ..golomb_coded_set.clone()
+            },
+            GolombCodedSet {
+                parameter: 64,
+                ..golomb_coded_set
+            },
+        ];
+        for invalid_set in &invalid_sets {
+            let json = serde_json::to_string(invalid_set).unwrap();
+            assert!(serde_json::from_str::<GolombCodedSet>(&json).is_err());
+        }
+    }
+}
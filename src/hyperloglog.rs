@@ -0,0 +1,444 @@
+//! HyperLogLog++ cardinality sketches: HyperLogLog with a sparse representation for small
+//! cardinalities and an empirical bias correction of the raw estimate for medium ones.
+use crate::bloom_filters::ElementHasher;
+use crate::error::Error;
+use crate::hyperloglog_bias::BIAS_TABLES;
+use crate::sets::Set;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+/// The precision of the sparse representation, as in HyperLogLog++.
+pub const SPARSE_PRECISION: u8 = 25;
+
+/// For every precision from 4 to 18, the cardinality up to which linear counting is more accurate
+/// than the bias-corrected estimate, taken from the HyperLogLog++ paper.
+const LINEAR_COUNTING_THRESHOLDS: [f64; 15] = [
+    10., 20., 40., 80., 220., 400., 900., 1800., 3100., 6500., 11500., 20000., 50000., 120000.,
+    350000.,
+];
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Registers {
+    /// Maps the `SPARSE_PRECISION` bit index of every observed hash to its largest rank.
+    Sparse(BTreeMap<u32, u8>),
+    /// One register per index of `precision` bits.
+    Dense(Vec<u8>),
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "HyperLogLogFields")
+)]
+pub struct HyperLogLog {
+    /// Between 4 and 18, the sketch has `2^precision` registers.
+    pub precision: u8,
+    pub registers: Registers,
+    pub hasher_id: u8,
+}
+
+/// A deserialized `HyperLogLog` before its precision and registers are checked.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct HyperLogLogFields {
+    precision: u8,
+    registers: Registers,
+    hasher_id: u8,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HyperLogLogFields> for HyperLogLog {
+    type Error = Error;
+
+    fn try_from(fields: HyperLogLogFields) -> Result<Self, Error> {
+        if !(4..=18).contains(&fields.precision) {
+            return Err(Error::InvalidParameter(
+                "the precision must be between 4 and 18",
+            ));
+        }
+
+        // A rank is at most one more than the number of bits after the index
+        let valid = match &fields.registers {
+            Registers::Sparse(entries) => entries.iter().all(|(index, rank)| {
+                *index < 1 << SPARSE_PRECISION && (1..=65 - SPARSE_PRECISION).contains(rank)
+            }),
+            Registers::Dense(registers) => {
+                registers.len() == 1 << fields.precision
+                    && registers
+                        .iter()
+                        .all(|register| *register <= 65 - fields.precision)
+            }
+        };
+        if !valid {
+            return Err(Error::InvalidParameter(
+                "the HyperLogLog registers are invalid",
+            ));
+        }
+
+        Ok(HyperLogLog {
+            precision: fields.precision,
+            registers: fields.registers,
+            hasher_id: fields.hasher_id,
+        })
+    }
+}
+
+impl HyperLogLog {
+    pub fn new<H: ElementHasher>(precision: u8) -> Self {
+        assert!((4..=18).contains(&precision));
+
+        HyperLogLog {
+            precision,
+            registers: Registers::Sparse(BTreeMap::new()),
+            hasher_id: H::ID,
+        }
+    }
+
+    pub fn from_elements<H: ElementHasher, I: IntoIterator<Item = usize>>(
+        precision: u8,
+        elements: I,
+    ) -> Self {
+        let mut hyperloglog = HyperLogLog::new::<H>(precision);
+        for element in elements {
+            hyperloglog.insert::<H>(&element);
+        }
+
+        hyperloglog
+    }
+
+    fn register_count(&self) -> usize {
+        1 << self.precision
+    }
+
+    /// Panics if `H` is not the hasher that built this sketch.
+    pub fn insert<H: ElementHasher>(&mut self, element: &usize) {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let hash = H::hash_element(element, 0) as u64;
+        match &mut self.registers {
+            Registers::Sparse(entries) => {
+                let index = (hash >> (64 - SPARSE_PRECISION)) as u32;
+                let rank = rank(hash << SPARSE_PRECISION, 64 - SPARSE_PRECISION);
+
+                let entry = entries.entry(index).or_insert(0);
+                *entry = (*entry).max(rank);
+
+                self.convert_if_large();
+            }
+            Registers::Dense(registers) => {
+                let index = (hash >> (64 - self.precision)) as usize;
+                let rank = rank(hash << self.precision, 64 - self.precision);
+
+                registers[index] = registers[index].max(rank);
+            }
+        }
+    }
+
+    /// Merges `other` into this sketch, after which it estimates the cardinality of the union.
+    /// Panics if the sketches have a different precision or hasher.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        self.try_merge(other)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_merge(&mut self, other: &HyperLogLog) -> Result<(), Error> {
+        if self.precision != other.precision || self.hasher_id != other.hasher_id {
+            return Err(Error::InvalidParameter(
+                "the sketches differ in their precision or hasher",
+            ));
+        }
+
+        if let (Registers::Sparse(entries), Registers::Sparse(other_entries)) =
+            (&mut self.registers, &other.registers)
+        {
+            for (index, rank) in other_entries {
+                let entry = entries.entry(*index).or_insert(0);
+                *entry = (*entry).max(*rank);
+            }
+
+            self.convert_if_large();
+            return Ok(());
+        }
+
+        let other_registers = other.dense_registers();
+        self.convert_to_dense();
+        if let Registers::Dense(registers) = &mut self.registers {
+            for (register, other_register) in registers.iter_mut().zip(other_registers) {
+                *register = (*register).max(other_register);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn estimate_cardinality(&self) -> f64 {
+        match &self.registers {
+            Registers::Sparse(entries) => {
+                // Linear counting over the 2^25 sparse registers is practically exact here
+                let register_count = (1u64 << SPARSE_PRECISION) as f64;
+                register_count * (register_count / (register_count - entries.len() as f64)).ln()
+            }
+            Registers::Dense(registers) => {
+                let m = self.register_count() as f64;
+                let sum: f64 = registers
+                    .iter()
+                    .map(|register| 2f64.powi(-(*register as i32)))
+                    .sum();
+                let raw_estimate = alpha(self.register_count()) * m * m / sum;
+
+                let estimate = if raw_estimate <= 5. * m {
+                    raw_estimate - self.estimate_bias(raw_estimate)
+                } else {
+                    raw_estimate
+                };
+
+                let zero_count = registers.iter().filter(|register| **register == 0).count();
+                if zero_count > 0 {
+                    let linear_count = m * (m / zero_count as f64).ln();
+                    if linear_count <= LINEAR_COUNTING_THRESHOLDS[self.precision as usize - 4] {
+                        return linear_count;
+                    }
+                }
+
+                estimate
+            }
+        }
+    }
+
+    /// Interpolates the bias of the raw estimate linearly between the two nearest entries of the
+    /// empirical bias table of this precision.
+    fn estimate_bias(&self, raw_estimate: f64) -> f64 {
+        let table = BIAS_TABLES[self.precision as usize - 4];
+        let next = table.partition_point(|(estimate, _)| *estimate < raw_estimate);
+        if next == 0 {
+            return table[0].1;
+        }
+        if next == table.len() {
+            return table[table.len() - 1].1;
+        }
+
+        let (low_estimate, low_bias) = table[next - 1];
+        let (high_estimate, high_bias) = table[next];
+        low_bias
+            + (high_bias - low_bias) * (raw_estimate - low_estimate)
+                / (high_estimate - low_estimate)
+    }
+
+    /// Switches to the dense representation once the sparse one would take more memory.
+    fn convert_if_large(&mut self) {
+        if let Registers::Sparse(entries) = &self.registers {
+            if entries.len() > self.register_count() / 4 {
+                self.convert_to_dense();
+            }
+        }
+    }
+
+    fn convert_to_dense(&mut self) {
+        self.registers = Registers::Dense(self.dense_registers());
+    }
+
+    fn dense_registers(&self) -> Vec<u8> {
+        match &self.registers {
+            Registers::Sparse(entries) => {
+                let extra_bits = SPARSE_PRECISION - self.precision;
+                let mut registers = vec![0; self.register_count()];
+
+                for (index, sparse_rank) in entries {
+                    let dense_index = (index >> extra_bits) as usize;
+                    // The bits of the sparse index that are not part of the dense index come
+                    // first in the part of the hash the dense rank is computed over
+                    let rank = if index & ((1 << extra_bits) - 1) == 0 {
+                        extra_bits + sparse_rank
+                    } else {
+                        rank((*index as u64) << (64 - extra_bits), extra_bits)
+                    };
+
+                    registers[dense_index] = registers[dense_index].max(rank);
+                }
+
+                registers
+            }
+            Registers::Dense(registers) => registers.clone(),
+        }
+    }
+}
+
+impl Set {
+    pub fn to_hyperloglog<H: ElementHasher>(&self, precision: u8) -> HyperLogLog {
+        HyperLogLog::from_elements::<H, _>(precision, self.elements.iter().copied())
+    }
+}
+
+/// Estimates the cardinality of the union of the sets summarized by the sketches. Panics if the
+/// sketches have a different precision or hasher.
+pub fn estimate_union_cardinality(sketches: &[HyperLogLog]) -> f64 {
+    try_estimate_union_cardinality(sketches).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn try_estimate_union_cardinality(sketches: &[HyperLogLog]) -> Result<f64, Error> {
+    match sketches.split_first() {
+        Some((first, rest)) => {
+            let mut union = first.clone();
+            for sketch in rest {
+                union.try_merge(sketch)?;
+            }
+            Ok(union.estimate_cardinality())
+        }
+        None => Ok(0.),
+    }
+}
+
+/// The position of the first one bit among the `bit_count` most significant bits of `bits`, or
+/// `bit_count + 1` if they are all zero.
+fn rank(bits: u64, bit_count: u8) -> u8 {
+    (bits.leading_zeros() as u8).min(bit_count) + 1
+}
+
+fn alpha(register_count: usize) -> f64 {
+    match register_count {
+        16 => 0.673,
+        32 => 0.697,
+        64 => 0.709,
+        m => 0.7213 / (1. + 1.079 / m as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::Xxh3Hasher;
+    use crate::error::Error;
+    use crate::hyperloglog::{
+        estimate_union_cardinality, try_estimate_union_cardinality, HyperLogLog, Registers,
+    };
+    use crate::sets::{gen_sets_with_union, Set};
+
+    type H = Xxh3Hasher;
+
+    fn assert_close(estimate: f64, exact: usize, relative_error: f64) {
+        assert!(
+            (estimate - exact as f64).abs() <= relative_error * exact as f64,
+            "estimate {} is too far from {}",
+            estimate,
+            exact
+        );
+    }
+
+    #[test]
+    fn test_sparse() {
+        let set = Set::random(500, 1_000_000);
+        let hyperloglog = set.to_hyperloglog::<H>(14);
+
+        assert!(matches!(hyperloglog.registers, Registers::Sparse(_)));
+        assert_close(hyperloglog.estimate_cardinality(), 500, 0.01);
+    }
+
+    #[test]
+    fn test_dense() {
+        let set = Set::random(100_000, 1_000_000_000);
+        let hyperloglog = set.to_hyperloglog::<H>(12);
+
+        assert!(matches!(hyperloglog.registers, Registers::Dense(_)));
+        assert_close(hyperloglog.estimate_cardinality(), 100_000, 0.07);
+    }
+
+    #[test]
+    fn test_bias_correction() {
+        // Between the linear counting threshold of 900 and five times the register count the
+        // raw estimate is biased, the corrected estimate should be unbiased on average
+        let register_count = 1024;
+        let trial_count = 100;
+        let cardinality = 2 * register_count;
+
+        let mean_estimate = (0..trial_count)
+            .map(|trial| {
+                let elements = trial * cardinality..(trial + 1) * cardinality;
+                HyperLogLog::from_elements::<H, _>(10, elements).estimate_cardinality()
+            })
+            .sum::<f64>()
+            / trial_count as f64;
+
+        assert_close(mean_estimate, cardinality, 0.01);
+    }
+
+    #[test]
+    fn test_union_cardinality() {
+        let sets = gen_sets_with_union(5, 4000, 1_000_000_000, 15_000);
+        let sketches: Vec<HyperLogLog> =
+            sets.iter().map(|set| set.to_hyperloglog::<H>(12)).collect();
+
+        assert_close(
+            estimate_union_cardinality(&sketches),
+            Set::union(&sets).len(),
+            0.07,
+        );
+    }
+
+    #[test]
+    fn test_merge_sparse_into_dense() {
+        let set1 = Set::random(50, 1_000_000);
+        let set2 = Set::random(20_000, 1_000_000);
+
+        let mut hyperloglog = set1.to_hyperloglog::<H>(10);
+        hyperloglog.merge(&set2.to_hyperloglog::<H>(10));
+
+        assert_eq!(
+            hyperloglog,
+            HyperLogLog::from_elements::<H, _>(10, set1.unify(&set2).elements)
+        );
+    }
+
+    #[test]
+    fn test_incompatible_sketches() {
+        let set = Set::random(100, 1_000_000);
+        let sketches = [set.to_hyperloglog::<H>(10), set.to_hyperloglog::<H>(12)];
+
+        assert_eq!(
+            try_estimate_union_cardinality(&sketches).unwrap_err(),
+            Error::InvalidParameter("the sketches differ in their precision or hasher")
+        );
+        assert!(sketches[0].clone().try_merge(&sketches[1]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        // A dense and a sparse sketch
+        let set = Set::random(1000, 1_000_000);
+        for hyperloglog in [set.to_hyperloglog::<H>(4), set.to_hyperloglog::<H>(14)] {
+            let json = serde_json::to_string(&hyperloglog).unwrap();
+            assert_eq!(
+                serde_json::from_str::<HyperLogLog>(&json).unwrap(),
+                hyperloglog
+            );
+        }
+
+        let invalid_sketches = [
+            HyperLogLog {
+                precision: 3,
+                ..HyperLogLog::new::<H>(4)
+            },
+            HyperLogLog {
+                registers: Registers::Dense(vec![0; 15]),
+                ..HyperLogLog::new::<H>(4)
+            },
+            HyperLogLog {
+                registers: Registers::Dense(vec![62; 16]),
+                ..HyperLogLog::new::<H>(4)
+            },
+            HyperLogLog {
+                registers: Registers::Sparse(vec![(1 << 25, 1)].into_iter().collect()),
+                ..HyperLogLog::new::<H>(4)
+            },
+        ];
+        for invalid_sketch in &invalid_sketches {
+            let json = serde_json::to_string(invalid_sketch).unwrap();
+            assert!(serde_json::from_str::<HyperLogLog>(&json).is_err());
+        }
+    }
+}
@@ -0,0 +1,1534 @@
+//! Generated by `cargo run --release --example hyperloglog_bias`, do not edit.
+
+// Some biases happen to round to well-known constants
+#![allow(clippy::approx_constant)]
+
+/// For every precision from 4 to 18, the mean raw estimate of a dense HyperLogLog sketch
+/// and its mean bias at cardinalities evenly spaced up to five times the register count.
+pub(crate) const BIAS_TABLES: [&[(f64, f64)]; 15] = [
+    &[
+        (10.768, 10.768),
+        (11.238, 10.238),
+        (11.723, 9.723),
+        (12.224, 9.224),
+        (12.740, 8.740),
+        (13.271, 8.271),
+        (13.818, 7.818),
+        (14.382, 7.382),
+        (14.961, 6.961),
+        (15.556, 6.556),
+        (16.166, 6.166),
+        (16.793, 5.793),
+        (17.436, 5.436),
+        (18.093, 5.093),
+        (18.767, 4.767),
+        (19.455, 4.455),
+        (20.156, 4.156),
+        (20.873, 3.873),
+        (21.606, 3.606),
+        (22.352, 3.352),
+        (23.110, 3.110),
+        (23.881, 2.881),
+        (24.667, 2.667),
+        (25.464, 2.464),
+        (26.274, 2.274),
+        (27.092, 2.092),
+        (27.924, 1.924),
+        (28.770, 1.770),
+        (29.622, 1.622),
+        (30.489, 1.489),
+        (31.361, 1.361),
+        (32.242, 1.242),
+        (33.133, 1.133),
+        (34.032, 1.032),
+        (34.942, 0.942),
+        (35.857, 0.857),
+        (36.777, 0.777),
+        (37.706, 0.706),
+        (38.640, 0.640),
+        (39.579, 0.579),
+        (40.527, 0.527),
+        (41.477, 0.477),
+        (42.432, 0.432),
+        (43.389, 0.389),
+        (44.355, 0.355),
+        (45.320, 0.320),
+        (46.293, 0.293),
+        (47.265, 0.265),
+        (48.242, 0.242),
+        (49.221, 0.221),
+        (50.196, 0.196),
+        (51.176, 0.176),
+        (52.160, 0.160),
+        (53.145, 0.145),
+        (54.137, 0.137),
+        (55.124, 0.124),
+        (56.113, 0.113),
+        (57.106, 0.106),
+        (58.098, 0.098),
+        (59.092, 0.092),
+        (60.078, 0.078),
+        (61.069, 0.069),
+        (62.063, 0.063),
+        (63.056, 0.056),
+        (64.046, 0.046),
+        (65.040, 0.040),
+        (66.036, 0.036),
+        (67.035, 0.035),
+        (68.033, 0.033),
+        (69.028, 0.028),
+        (70.022, 0.022),
+        (71.018, 0.018),
+        (72.012, 0.012),
+        (73.009, 0.009),
+        (74.011, 0.011),
+        (75.016, 0.016),
+        (76.015, 0.015),
+        (77.009, 0.009),
+        (78.008, 0.008),
+        (79.008, 0.008),
+        (80.009, 0.009),
+    ],
+    &[
+        (22.304, 22.304),
+        (23.262, 21.262),
+        (23.751, 20.751),
+        (24.754, 19.754),
+        (25.266, 19.266),
+        (26.314, 18.314),
+        (27.392, 17.392),
+        (27.942, 16.942),
+        (29.066, 16.066),
+        (29.638, 15.638),
+        (30.807, 14.807),
+        (32.005, 14.005),
+        (32.617, 13.617),
+        (33.857, 12.857),
+        (34.489, 12.489),
+        (35.773, 11.773),
+        (37.089, 11.089),
+        (37.759, 10.759),
+        (39.119, 10.119),
+        (39.808, 9.808),
+        (41.207, 9.207),
+        (42.637, 8.637),
+        (43.362, 8.362),
+        (44.830, 7.830),
+        (45.574, 7.574),
+        (47.081, 7.081),
+        (48.608, 6.608),
+        (49.384, 6.384),
+        (50.951, 5.951),
+        (51.743, 5.743),
+        (53.345, 5.345),
+        (54.970, 4.970),
+        (55.789, 4.789),
+        (57.449, 4.449),
+        (58.287, 4.287),
+        (59.973, 3.973),
+        (61.680, 3.680),
+        (62.540, 3.540),
+        (64.271, 3.271),
+        (65.143, 3.143),
+        (66.903, 2.903),
+        (68.680, 2.680),
+        (69.576, 2.576),
+        (71.371, 2.371),
+        (72.273, 2.273),
+        (74.081, 2.081),
+        (75.899, 1.899),
+        (76.821, 1.821),
+        (78.665, 1.665),
+        (79.593, 1.593),
+        (81.464, 1.464),
+        (83.336, 1.336),
+        (84.277, 1.277),
+        (86.165, 1.165),
+        (87.114, 1.114),
+        (89.006, 1.006),
+        (90.914, 0.914),
+        (91.870, 0.870),
+        (93.793, 0.793),
+        (94.753, 0.753),
+        (96.688, 0.688),
+        (98.635, 0.635),
+        (99.604, 0.604),
+        (101.544, 0.544),
+        (102.523, 0.523),
+        (104.469, 0.469),
+        (106.426, 0.426),
+        (107.405, 0.405),
+        (109.370, 0.370),
+        (110.347, 0.347),
+        (112.311, 0.311),
+        (114.282, 0.282),
+        (115.264, 0.264),
+        (117.243, 0.243),
+        (118.230, 0.230),
+        (120.198, 0.198),
+        (122.172, 0.172),
+        (123.163, 0.163),
+        (125.144, 0.144),
+        (126.137, 0.137),
+        (128.131, 0.131),
+        (130.114, 0.114),
+        (131.098, 0.098),
+        (133.092, 0.092),
+        (134.086, 0.086),
+        (136.080, 0.080),
+        (138.069, 0.069),
+        (139.067, 0.067),
+        (141.049, 0.049),
+        (142.051, 0.051),
+        (144.039, 0.039),
+        (146.021, 0.021),
+        (147.014, 0.014),
+        (149.013, 0.013),
+        (150.015, 0.015),
+        (152.019, 0.019),
+        (154.015, 0.015),
+        (155.011, 0.011),
+        (156.999, -0.001),
+        (158.000, -0.000),
+        (159.992, -0.008),
+    ],
+    &[
+        (45.376, 45.376),
+        (46.821, 43.821),
+        (48.297, 42.297),
+        (50.318, 40.318),
+        (51.873, 38.873),
+        (53.462, 37.462),
+        (55.084, 36.084),
+        (56.739, 34.739),
+        (58.997, 32.997),
+        (60.731, 31.731),
+        (62.494, 30.494),
+        (64.291, 29.291),
+        (66.121, 28.121),
+        (68.611, 26.611),
+        (70.516, 25.516),
+        (72.454, 24.454),
+        (74.422, 23.422),
+        (76.424, 22.424),
+        (79.139, 21.139),
+        (81.213, 20.213),
+        (83.316, 19.316),
+        (85.448, 18.448),
+        (87.613, 17.613),
+        (90.540, 16.540),
+        (92.765, 15.765),
+        (95.016, 15.016),
+        (97.296, 14.296),
+        (99.611, 13.611),
+        (102.720, 12.720),
+        (105.089, 12.089),
+        (107.483, 11.483),
+        (109.900, 10.900),
+        (112.342, 10.342),
+        (115.639, 9.639),
+        (118.137, 9.137),
+        (120.657, 8.657),
+        (123.199, 8.199),
+        (125.752, 7.752),
+        (129.207, 7.207),
+        (131.804, 6.804),
+        (134.420, 6.420),
+        (137.053, 6.053),
+        (139.703, 5.703),
+        (143.258, 5.258),
+        (145.961, 4.961),
+        (148.670, 4.670),
+        (151.388, 4.388),
+        (154.123, 4.123),
+        (157.788, 3.788),
+        (160.560, 3.560),
+        (163.321, 3.321),
+        (166.109, 3.109),
+        (168.907, 2.907),
+        (172.666, 2.666),
+        (175.504, 2.504),
+        (178.348, 2.348),
+        (181.197, 2.197),
+        (184.043, 2.043),
+        (187.874, 1.874),
+        (190.737, 1.737),
+        (193.624, 1.624),
+        (196.498, 1.498),
+        (199.400, 1.400),
+        (203.274, 1.274),
+        (206.195, 1.195),
+        (209.108, 1.108),
+        (212.030, 1.030),
+        (214.954, 0.954),
+        (218.857, 0.857),
+        (221.784, 0.784),
+        (224.714, 0.714),
+        (227.668, 0.668),
+        (230.622, 0.622),
+        (234.537, 0.537),
+        (237.499, 0.499),
+        (240.444, 0.444),
+        (243.392, 0.392),
+        (246.339, 0.339),
+        (250.295, 0.295),
+        (253.269, 0.269),
+        (256.266, 0.266),
+        (259.220, 0.220),
+        (262.211, 0.211),
+        (266.184, 0.184),
+        (269.165, 0.165),
+        (272.143, 0.143),
+        (275.123, 0.123),
+        (278.096, 0.096),
+        (282.087, 0.087),
+        (285.086, 0.086),
+        (288.069, 0.069),
+        (291.078, 0.078),
+        (294.042, 0.042),
+        (298.020, 0.020),
+        (301.013, 0.013),
+        (304.020, 0.020),
+        (307.031, 0.031),
+        (310.025, 0.025),
+        (314.014, 0.014),
+        (317.007, 0.007),
+        (319.974, -0.026),
+    ],
+    &[
+        (91.555, 91.555),
+        (94.461, 88.461),
+        (97.933, 84.933),
+        (100.978, 81.978),
+        (104.614, 78.614),
+        (107.803, 75.803),
+        (111.055, 73.055),
+        (114.937, 69.937),
+        (118.334, 67.334),
+        (122.379, 64.379),
+        (125.913, 61.913),
+        (129.518, 59.518),
+        (133.808, 56.808),
+        (137.555, 54.555),
+        (142.000, 52.000),
+        (145.879, 49.879),
+        (149.809, 47.809),
+        (154.496, 45.496),
+        (158.580, 43.580),
+        (163.408, 41.408),
+        (167.605, 39.605),
+        (171.870, 37.870),
+        (176.920, 35.920),
+        (181.312, 34.312),
+        (186.513, 32.513),
+        (191.013, 31.013),
+        (195.583, 29.583),
+        (200.965, 27.965),
+        (205.629, 26.629),
+        (211.149, 25.149),
+        (215.934, 23.934),
+        (220.759, 22.759),
+        (226.447, 21.447),
+        (231.387, 20.387),
+        (237.186, 19.186),
+        (242.208, 18.208),
+        (247.270, 17.270),
+        (253.234, 16.234),
+        (258.385, 15.385),
+        (264.432, 14.432),
+        (269.643, 13.643),
+        (274.905, 12.905),
+        (281.068, 12.068),
+        (286.426, 11.426),
+        (292.701, 10.701),
+        (298.089, 10.089),
+        (303.510, 9.510),
+        (309.877, 8.877),
+        (315.377, 8.377),
+        (321.798, 7.798),
+        (327.349, 7.349),
+        (332.892, 6.892),
+        (339.403, 6.403),
+        (345.033, 6.033),
+        (351.622, 5.622),
+        (357.296, 5.296),
+        (362.965, 4.965),
+        (369.634, 4.634),
+        (375.357, 4.357),
+        (382.063, 4.063),
+        (387.849, 3.849),
+        (393.601, 3.601),
+        (400.333, 3.333),
+        (406.131, 3.131),
+        (412.912, 2.912),
+        (418.719, 2.719),
+        (424.554, 2.554),
+        (431.364, 2.364),
+        (437.223, 2.223),
+        (444.061, 2.061),
+        (449.907, 1.907),
+        (455.817, 1.817),
+        (462.703, 1.703),
+        (468.618, 1.618),
+        (475.512, 1.512),
+        (481.426, 1.426),
+        (487.321, 1.321),
+        (494.221, 1.221),
+        (500.112, 1.112),
+        (507.043, 1.043),
+        (512.970, 0.970),
+        (518.920, 0.920),
+        (525.837, 0.837),
+        (531.787, 0.787),
+        (538.716, 0.716),
+        (544.660, 0.660),
+        (550.621, 0.621),
+        (557.597, 0.597),
+        (563.545, 0.545),
+        (570.527, 0.527),
+        (576.460, 0.460),
+        (582.474, 0.474),
+        (589.471, 0.471),
+        (595.447, 0.447),
+        (602.422, 0.422),
+        (608.371, 0.371),
+        (614.354, 0.354),
+        (621.293, 0.293),
+        (627.261, 0.261),
+        (634.265, 0.265),
+        (640.279, 0.279),
+    ],
+    &[
+        (183.878, 183.878),
+        (190.190, 177.190),
+        (196.658, 170.658),
+        (202.757, 164.757),
+        (209.513, 158.513),
+        (216.419, 152.419),
+        (223.483, 146.483),
+        (230.701, 140.701),
+        (237.493, 135.493),
+        (244.984, 129.984),
+        (252.655, 124.655),
+        (260.471, 119.471),
+        (268.446, 114.446),
+        (275.917, 109.917),
+        (284.162, 105.162),
+        (292.564, 100.564),
+        (301.105, 96.105),
+        (309.792, 91.792),
+        (317.925, 87.925),
+        (326.898, 83.898),
+        (335.958, 79.958),
+        (345.184, 76.184),
+        (354.547, 72.547),
+        (363.293, 69.293),
+        (372.906, 65.906),
+        (382.653, 62.653),
+        (392.562, 59.562),
+        (402.581, 56.581),
+        (411.904, 53.904),
+        (422.122, 51.122),
+        (432.451, 48.451),
+        (442.889, 45.889),
+        (453.441, 43.441),
+        (463.277, 41.277),
+        (474.029, 39.029),
+        (484.872, 36.872),
+        (495.840, 34.840),
+        (506.917, 32.917),
+        (517.201, 31.201),
+        (528.398, 29.398),
+        (539.708, 27.708),
+        (551.158, 26.158),
+        (562.634, 24.634),
+        (573.280, 23.280),
+        (584.885, 21.885),
+        (596.592, 20.592),
+        (608.341, 19.341),
+        (620.172, 18.172),
+        (631.151, 17.151),
+        (643.099, 16.099),
+        (655.097, 15.097),
+        (667.153, 14.153),
+        (679.267, 13.267),
+        (690.575, 12.575),
+        (702.751, 11.751),
+        (715.030, 11.030),
+        (727.265, 10.265),
+        (739.594, 9.594),
+        (750.996, 8.996),
+        (763.377, 8.377),
+        (775.837, 7.837),
+        (788.338, 7.338),
+        (800.919, 6.919),
+        (812.482, 6.482),
+        (825.024, 6.024),
+        (837.635, 5.635),
+        (850.208, 5.208),
+        (862.873, 4.873),
+        (874.621, 4.621),
+        (887.277, 4.277),
+        (899.941, 3.941),
+        (912.735, 3.735),
+        (925.450, 3.450),
+        (937.241, 3.241),
+        (950.029, 3.029),
+        (962.709, 2.709),
+        (975.532, 2.532),
+        (988.352, 2.352),
+        (1000.216, 2.216),
+        (1013.122, 2.122),
+        (1025.991, 1.991),
+        (1038.923, 1.923),
+        (1051.733, 1.733),
+        (1063.616, 1.616),
+        (1076.520, 1.520),
+        (1089.433, 1.433),
+        (1102.342, 1.342),
+        (1115.239, 1.239),
+        (1127.145, 1.145),
+        (1140.062, 1.062),
+        (1152.986, 0.986),
+        (1166.000, 1.000),
+        (1178.953, 0.953),
+        (1191.031, 1.031),
+        (1204.035, 1.035),
+        (1217.026, 1.026),
+        (1229.920, 0.920),
+        (1242.899, 0.899),
+        (1254.856, 0.856),
+        (1267.869, 0.869),
+        (1280.849, 0.849),
+    ],
+    &[
+        (368.529, 368.529),
+        (381.167, 355.167),
+        (393.604, 342.604),
+        (406.836, 329.836),
+        (419.841, 317.841),
+        (433.672, 305.672),
+        (447.810, 293.810),
+        (461.712, 282.712),
+        (476.466, 271.466),
+        (490.910, 260.910),
+        (506.234, 250.234),
+        (521.866, 239.866),
+        (537.190, 230.190),
+        (553.397, 220.397),
+        (569.285, 211.285),
+        (586.070, 202.070),
+        (603.194, 193.194),
+        (619.892, 184.892),
+        (637.497, 176.497),
+        (654.710, 168.710),
+        (672.885, 160.885),
+        (691.367, 153.367),
+        (709.348, 146.348),
+        (728.319, 139.319),
+        (746.787, 132.787),
+        (766.307, 126.307),
+        (786.071, 120.071),
+        (805.313, 114.313),
+        (825.487, 108.487),
+        (845.130, 103.130),
+        (865.748, 97.748),
+        (886.656, 92.656),
+        (906.912, 87.912),
+        (928.167, 83.167),
+        (948.873, 78.873),
+        (970.602, 74.602),
+        (992.471, 70.471),
+        (1013.752, 66.752),
+        (1035.928, 62.928),
+        (1057.494, 59.494),
+        (1080.104, 56.104),
+        (1102.986, 52.986),
+        (1125.004, 50.004),
+        (1148.081, 47.081),
+        (1170.409, 44.409),
+        (1193.711, 41.711),
+        (1217.180, 39.180),
+        (1239.987, 36.987),
+        (1263.732, 34.732),
+        (1286.698, 32.698),
+        (1310.608, 30.608),
+        (1334.602, 28.602),
+        (1357.876, 26.876),
+        (1382.244, 25.244),
+        (1405.796, 23.796),
+        (1430.178, 22.178),
+        (1454.642, 20.642),
+        (1478.374, 19.374),
+        (1502.950, 17.950),
+        (1526.776, 16.776),
+        (1551.658, 15.658),
+        (1576.572, 14.572),
+        (1600.606, 13.606),
+        (1625.737, 12.737),
+        (1649.984, 11.984),
+        (1675.208, 11.208),
+        (1700.483, 10.483),
+        (1724.730, 9.730),
+        (1750.161, 9.161),
+        (1774.528, 8.528),
+        (1799.900, 7.900),
+        (1825.299, 7.299),
+        (1849.762, 6.762),
+        (1875.319, 6.319),
+        (1899.951, 5.951),
+        (1925.523, 5.523),
+        (1951.239, 5.239),
+        (1976.008, 5.008),
+        (2001.553, 4.553),
+        (2026.117, 4.117),
+        (2051.888, 3.888),
+        (2077.528, 3.528),
+        (2102.312, 3.312),
+        (2128.089, 3.089),
+        (2153.126, 3.126),
+        (2178.929, 2.929),
+        (2204.854, 2.854),
+        (2229.902, 2.902),
+        (2255.717, 2.717),
+        (2280.636, 2.636),
+        (2306.572, 2.572),
+        (2332.346, 2.346),
+        (2357.187, 2.187),
+        (2383.258, 2.258),
+        (2408.188, 2.188),
+        (2434.220, 2.220),
+        (2460.318, 2.318),
+        (2485.119, 2.119),
+        (2511.069, 2.069),
+        (2535.929, 1.929),
+        (2561.744, 1.744),
+    ],
+    &[
+        (737.834, 737.834),
+        (762.621, 711.621),
+        (787.995, 685.995),
+        (814.485, 660.485),
+        (841.069, 636.069),
+        (868.214, 612.214),
+        (895.921, 588.921),
+        (924.259, 566.259),
+        (953.706, 543.706),
+        (983.197, 522.197),
+        (1013.273, 501.273),
+        (1043.964, 480.964),
+        (1075.182, 461.182),
+        (1107.601, 441.601),
+        (1139.991, 422.991),
+        (1172.944, 404.944),
+        (1206.453, 387.453),
+        (1240.496, 370.496),
+        (1275.755, 353.755),
+        (1310.890, 337.890),
+        (1346.562, 322.562),
+        (1382.770, 307.770),
+        (1419.502, 293.502),
+        (1457.484, 279.484),
+        (1495.334, 266.334),
+        (1533.538, 253.538),
+        (1572.281, 241.281),
+        (1611.436, 229.436),
+        (1651.934, 217.934),
+        (1691.892, 206.892),
+        (1732.454, 196.454),
+        (1773.465, 186.465),
+        (1814.782, 176.782),
+        (1857.343, 167.343),
+        (1899.353, 158.353),
+        (1941.986, 149.986),
+        (1984.709, 141.709),
+        (2027.804, 133.804),
+        (2072.319, 126.319),
+        (2116.038, 119.038),
+        (2160.298, 112.298),
+        (2204.959, 105.959),
+        (2249.995, 99.995),
+        (2296.021, 94.021),
+        (2341.514, 88.514),
+        (2387.129, 83.129),
+        (2433.292, 78.292),
+        (2479.585, 73.585),
+        (2526.831, 68.831),
+        (2573.764, 64.764),
+        (2620.736, 60.736),
+        (2668.063, 57.063),
+        (2715.369, 53.369),
+        (2764.131, 50.131),
+        (2811.874, 46.874),
+        (2859.954, 43.954),
+        (2908.188, 41.188),
+        (2956.670, 38.670),
+        (3006.119, 36.119),
+        (3054.828, 33.828),
+        (3103.558, 31.558),
+        (3152.529, 29.529),
+        (3201.547, 27.547),
+        (3251.733, 25.733),
+        (3300.942, 23.942),
+        (3350.381, 22.381),
+        (3399.946, 20.946),
+        (3449.463, 19.463),
+        (3500.251, 18.251),
+        (3549.799, 16.799),
+        (3599.612, 15.612),
+        (3649.544, 14.544),
+        (3699.361, 13.361),
+        (3750.456, 12.456),
+        (3800.690, 11.690),
+        (3850.919, 10.919),
+        (3901.342, 10.342),
+        (3951.881, 9.881),
+        (4003.455, 9.455),
+        (4054.026, 9.026),
+        (4104.531, 8.531),
+        (4154.982, 7.982),
+        (4205.800, 7.800),
+        (4257.415, 7.415),
+        (4307.533, 6.533),
+        (4358.280, 6.280),
+        (4409.280, 6.280),
+        (4460.091, 6.091),
+        (4511.916, 5.916),
+        (4562.498, 5.498),
+        (4613.106, 5.106),
+        (4663.925, 4.925),
+        (4714.590, 4.590),
+        (4766.089, 4.089),
+        (4816.688, 3.688),
+        (4867.536, 3.536),
+        (4918.279, 3.279),
+        (4969.020, 3.020),
+        (5020.883, 2.883),
+        (5071.913, 2.913),
+        (5122.517, 2.517),
+    ],
+    &[
+        (1476.445, 1476.445),
+        (1526.113, 1424.113),
+        (1577.381, 1372.381),
+        (1629.352, 1322.352),
+        (1682.992, 1272.992),
+        (1737.277, 1225.277),
+        (1792.781, 1178.781),
+        (1850.074, 1133.074),
+        (1907.905, 1088.905),
+        (1967.441, 1045.441),
+        (2027.603, 1003.603),
+        (2088.878, 962.878),
+        (2151.995, 922.995),
+        (2215.605, 884.605),
+        (2281.093, 847.093),
+        (2347.170, 811.170),
+        (2414.218, 776.218),
+        (2483.080, 742.080),
+        (2552.269, 709.269),
+        (2623.329, 677.329),
+        (2694.733, 646.733),
+        (2767.052, 617.052),
+        (2841.270, 588.270),
+        (2915.689, 560.689),
+        (2991.893, 533.893),
+        (3068.257, 508.257),
+        (3145.513, 483.513),
+        (3224.715, 459.715),
+        (3303.932, 436.932),
+        (3384.885, 414.885),
+        (3465.794, 393.794),
+        (3547.594, 373.594),
+        (3630.972, 353.972),
+        (3714.460, 335.460),
+        (3799.286, 317.286),
+        (3884.391, 300.391),
+        (3970.443, 284.443),
+        (4057.775, 268.775),
+        (4144.969, 253.969),
+        (4233.747, 239.747),
+        (4322.338, 226.338),
+        (4411.590, 213.590),
+        (4502.292, 201.292),
+        (4592.492, 189.492),
+        (4684.352, 178.352),
+        (4775.632, 167.632),
+        (4867.496, 157.496),
+        (4960.756, 147.756),
+        (5053.995, 138.995),
+        (5148.473, 130.473),
+        (5242.752, 122.752),
+        (5337.233, 115.233),
+        (5433.102, 108.102),
+        (5527.860, 100.860),
+        (5624.626, 94.626),
+        (5720.657, 88.657),
+        (5816.888, 82.888),
+        (5914.615, 77.615),
+        (6011.416, 72.416),
+        (6109.545, 67.545),
+        (6207.000, 63.000),
+        (6304.864, 58.864),
+        (6404.215, 55.215),
+        (6502.532, 51.532),
+        (6601.985, 47.985),
+        (6699.905, 43.905),
+        (6798.326, 40.326),
+        (6898.537, 37.537),
+        (6997.564, 34.564),
+        (7098.062, 32.062),
+        (7197.779, 29.779),
+        (7297.841, 27.841),
+        (7398.939, 25.939),
+        (7499.038, 24.038),
+        (7600.470, 22.470),
+        (7700.993, 20.993),
+        (7801.591, 19.591),
+        (7902.586, 17.586),
+        (8002.703, 15.703),
+        (8103.891, 13.891),
+        (8204.518, 12.518),
+        (8305.896, 11.896),
+        (8407.805, 10.805),
+        (8508.496, 9.496),
+        (8610.657, 8.657),
+        (8712.564, 8.564),
+        (8814.204, 8.204),
+        (8916.490, 7.490),
+        (9018.064, 7.064),
+        (9121.028, 7.028),
+        (9222.674, 6.674),
+        (9324.148, 6.148),
+        (9426.453, 5.453),
+        (9528.685, 5.685),
+        (9631.732, 5.732),
+        (9733.328, 5.328),
+        (9835.015, 5.015),
+        (9937.886, 4.886),
+        (10039.989, 4.989),
+        (10142.643, 4.643),
+        (10244.509, 4.509),
+    ],
+    &[
+        (2953.667, 2953.667),
+        (3053.468, 2848.468),
+        (3155.610, 2745.610),
+        (3259.584, 2645.584),
+        (3366.369, 2547.369),
+        (3475.651, 2451.651),
+        (3587.195, 2358.195),
+        (3701.120, 2267.120),
+        (3816.909, 2178.909),
+        (3935.454, 2092.454),
+        (4056.466, 2008.466),
+        (4179.783, 1926.783),
+        (4305.457, 1847.457),
+        (4432.731, 1770.731),
+        (4562.938, 1695.938),
+        (4695.567, 1623.567),
+        (4830.410, 1553.410),
+        (4967.264, 1485.264),
+        (5105.223, 1419.223),
+        (5246.162, 1355.162),
+        (5389.695, 1293.695),
+        (5535.371, 1234.371),
+        (5683.134, 1177.134),
+        (5832.151, 1122.151),
+        (5983.544, 1068.544),
+        (6136.648, 1016.648),
+        (6291.303, 966.303),
+        (6448.435, 918.435),
+        (6607.065, 873.065),
+        (6767.742, 828.742),
+        (6930.245, 786.245),
+        (7094.526, 745.526),
+        (7260.564, 706.564),
+        (7427.643, 669.643),
+        (7596.218, 633.218),
+        (7766.739, 598.739),
+        (7939.260, 566.260),
+        (8113.834, 535.834),
+        (8288.072, 506.072),
+        (8464.900, 477.900),
+        (8643.085, 451.085),
+        (8822.803, 425.803),
+        (9002.768, 400.768),
+        (9183.275, 377.275),
+        (9366.514, 355.514),
+        (9549.842, 333.842),
+        (9734.345, 313.345),
+        (9919.714, 293.714),
+        (10106.061, 276.061),
+        (10294.208, 259.208),
+        (10482.626, 242.626),
+        (10671.779, 226.779),
+        (10862.725, 212.725),
+        (11053.511, 199.511),
+        (11245.469, 186.469),
+        (11438.687, 174.687),
+        (11631.411, 162.411),
+        (11825.818, 151.818),
+        (12018.737, 140.737),
+        (12213.477, 130.477),
+        (12409.481, 121.481),
+        (12606.576, 113.576),
+        (12803.033, 105.033),
+        (12999.564, 97.564),
+        (13196.011, 89.011),
+        (13394.686, 82.686),
+        (13593.465, 76.465),
+        (13793.190, 71.190),
+        (13992.266, 66.266),
+        (14191.636, 60.636),
+        (14392.968, 56.968),
+        (14594.367, 53.367),
+        (14795.275, 49.275),
+        (14995.979, 45.979),
+        (15198.150, 43.150),
+        (15400.047, 40.047),
+        (15601.302, 36.302),
+        (15803.870, 33.870),
+        (16004.721, 30.721),
+        (16207.718, 28.718),
+        (16410.949, 26.949),
+        (16613.139, 24.139),
+        (16815.455, 21.455),
+        (17017.911, 19.911),
+        (17221.998, 18.998),
+        (17424.714, 16.714),
+        (17628.442, 15.442),
+        (17832.715, 14.715),
+        (18034.393, 12.393),
+        (18238.385, 11.385),
+        (18441.431, 9.431),
+        (18644.526, 7.526),
+        (18848.871, 6.871),
+        (19052.267, 6.267),
+        (19254.798, 3.798),
+        (19459.600, 3.600),
+        (19662.353, 1.353),
+        (19865.734, -0.266),
+        (20069.093, -0.907),
+        (20273.612, -1.388),
+        (20477.214, -2.786),
+    ],
+    &[
+        (5908.111, 5908.111),
+        (6107.723, 5697.723),
+        (6311.204, 5492.204),
+        (6520.144, 5291.144),
+        (6733.331, 5095.331),
+        (6951.723, 4903.723),
+        (7175.028, 4717.028),
+        (7402.100, 4535.100),
+        (7634.668, 4357.668),
+        (7871.222, 4185.222),
+        (8113.043, 4017.043),
+        (8359.650, 3853.650),
+        (8610.319, 3695.319),
+        (8865.864, 3540.864),
+        (9125.836, 3391.836),
+        (9391.357, 3247.357),
+        (9661.050, 3107.050),
+        (9934.176, 2971.176),
+        (10212.693, 2839.693),
+        (10494.465, 2712.465),
+        (10781.382, 2589.382),
+        (11072.031, 2470.031),
+        (11366.470, 2355.470),
+        (11665.845, 2244.845),
+        (11968.820, 2138.820),
+        (12275.895, 2035.895),
+        (12586.816, 1936.816),
+        (12900.071, 1841.071),
+        (13218.303, 1749.303),
+        (13539.420, 1661.420),
+        (13865.210, 1577.210),
+        (14194.079, 1496.079),
+        (14526.396, 1419.396),
+        (14863.006, 1346.006),
+        (15202.127, 1276.127),
+        (15544.150, 1208.150),
+        (15887.456, 1141.456),
+        (16235.773, 1080.773),
+        (16586.024, 1021.024),
+        (16938.502, 964.502),
+        (17293.938, 909.938),
+        (17652.100, 858.100),
+        (18011.798, 808.798),
+        (18375.600, 762.600),
+        (18739.713, 717.713),
+        (19107.399, 675.399),
+        (19476.999, 634.999),
+        (19848.096, 597.096),
+        (20222.460, 561.460),
+        (20597.540, 527.540),
+        (20973.546, 493.546),
+        (21352.931, 462.931),
+        (21734.118, 435.118),
+        (22116.127, 407.127),
+        (22499.402, 381.402),
+        (22885.772, 357.772),
+        (23273.385, 335.385),
+        (23658.477, 311.477),
+        (24047.170, 290.170),
+        (24437.182, 271.182),
+        (24828.377, 252.377),
+        (25220.337, 234.337),
+        (25614.284, 219.284),
+        (26010.078, 205.078),
+        (26405.023, 191.023),
+        (26802.265, 178.265),
+        (27200.726, 166.726),
+        (27596.634, 153.634),
+        (27994.576, 141.576),
+        (28392.116, 130.116),
+        (28792.629, 120.629),
+        (29192.322, 110.322),
+        (29595.484, 104.484),
+        (29998.146, 97.146),
+        (30401.727, 91.727),
+        (30806.785, 86.785),
+        (31210.121, 80.121),
+        (31613.378, 74.378),
+        (32017.448, 68.448),
+        (32420.478, 62.478),
+        (32823.903, 55.903),
+        (33228.326, 50.326),
+        (33635.217, 48.217),
+        (34039.090, 42.090),
+        (34445.874, 39.874),
+        (34851.636, 35.636),
+        (35258.027, 32.027),
+        (35662.977, 27.977),
+        (36068.519, 23.519),
+        (36475.895, 21.895),
+        (36883.958, 19.958),
+        (37288.625, 14.625),
+        (37694.991, 11.991),
+        (38103.695, 10.695),
+        (38511.476, 9.476),
+        (38920.001, 8.001),
+        (39327.899, 5.899),
+        (39735.788, 4.788),
+        (40146.629, 5.629),
+        (40556.733, 6.733),
+        (40966.539, 6.539),
+    ],
+    &[
+        (11817.001, 11817.001),
+        (12215.314, 11396.314),
+        (12623.486, 10985.486),
+        (13041.236, 10583.236),
+        (13467.765, 10190.765),
+        (13903.970, 9807.970),
+        (14349.544, 9434.544),
+        (14805.044, 9071.044),
+        (15270.127, 8716.127),
+        (15743.596, 8370.596),
+        (16227.674, 8035.674),
+        (16720.294, 7709.294),
+        (17221.919, 7391.919),
+        (17734.243, 7084.243),
+        (18253.760, 6784.760),
+        (18782.802, 6494.802),
+        (19320.893, 6213.893),
+        (19867.718, 5941.718),
+        (20425.042, 5679.042),
+        (20989.791, 5424.791),
+        (21561.813, 5177.813),
+        (22143.950, 4940.950),
+        (22732.835, 4710.835),
+        (23330.814, 4488.814),
+        (23937.364, 4276.364),
+        (24550.878, 4070.878),
+        (25170.933, 3871.933),
+        (25799.115, 3681.115),
+        (26436.236, 3498.236),
+        (27079.167, 3322.167),
+        (27731.613, 3155.613),
+        (28387.646, 2992.646),
+        (29050.237, 2836.237),
+        (29721.056, 2687.056),
+        (30397.775, 2544.775),
+        (31078.407, 2406.407),
+        (31764.932, 2273.932),
+        (32461.564, 2151.564),
+        (33160.285, 2030.285),
+        (33866.374, 1917.374),
+        (34578.408, 1810.408),
+        (35293.404, 1706.404),
+        (36017.639, 1611.639),
+        (36742.151, 1516.151),
+        (37471.808, 1426.808),
+        (38209.599, 1345.599),
+        (38950.340, 1267.340),
+        (39695.509, 1193.509),
+        (40445.391, 1123.391),
+        (41196.696, 1055.696),
+        (41947.457, 987.457),
+        (42703.462, 924.462),
+        (43464.897, 866.897),
+        (44230.463, 812.463),
+        (44995.880, 758.880),
+        (45767.745, 711.745),
+        (46542.606, 667.606),
+        (47316.741, 622.741),
+        (48097.140, 583.140),
+        (48879.884, 546.884),
+        (49660.626, 508.626),
+        (50445.200, 474.200),
+        (51231.566, 441.566),
+        (52021.525, 411.525),
+        (52811.524, 382.524),
+        (53603.592, 355.592),
+        (54393.851, 326.851),
+        (55187.975, 301.975),
+        (55985.074, 279.074),
+        (56782.151, 257.151),
+        (57578.899, 234.899),
+        (58374.660, 211.660),
+        (59174.648, 192.648),
+        (59980.353, 178.353),
+        (60785.913, 164.913),
+        (61585.435, 145.435),
+        (62382.927, 123.927),
+        (63193.190, 115.190),
+        (64009.604, 111.604),
+        (64820.693, 103.693),
+        (65622.789, 86.789),
+        (66429.698, 74.698),
+        (67238.142, 64.142),
+        (68053.563, 59.563),
+        (68865.800, 52.800),
+        (69677.453, 45.453),
+        (70493.250, 42.250),
+        (71304.321, 34.321),
+        (72115.976, 25.976),
+        (72939.298, 30.298),
+        (73750.513, 22.513),
+        (74566.888, 19.888),
+        (75382.548, 16.548),
+        (76196.910, 10.910),
+        (77012.985, 7.985),
+        (77828.402, 4.402),
+        (78639.809, -3.191),
+        (79457.189, -4.811),
+        (80277.336, -4.664),
+        (81090.187, -10.813),
+        (81910.075, -9.925),
+    ],
+    &[
+        (23634.780, 23634.780),
+        (24431.900, 22793.900),
+        (25248.902, 21971.902),
+        (26084.105, 21169.105),
+        (26938.273, 20384.273),
+        (27810.875, 19618.875),
+        (28702.696, 18872.696),
+        (29613.377, 18144.377),
+        (30542.396, 17435.396),
+        (31491.531, 16745.531),
+        (32456.432, 16072.432),
+        (33441.503, 15419.503),
+        (34446.212, 14785.212),
+        (35467.418, 14168.418),
+        (36507.650, 13569.650),
+        (37565.809, 12989.809),
+        (38642.248, 12428.248),
+        (39735.367, 11882.367),
+        (40845.373, 11354.373),
+        (41975.282, 10845.282),
+        (43123.231, 10355.231),
+        (44283.314, 9877.314),
+        (45461.441, 9416.441),
+        (46656.365, 8973.365),
+        (47868.024, 8546.024),
+        (49091.998, 8131.998),
+        (50337.901, 7739.901),
+        (51594.041, 7357.041),
+        (52864.615, 6989.615),
+        (54148.078, 6634.078),
+        (55445.689, 6293.689),
+        (56760.208, 5970.208),
+        (58085.110, 5656.110),
+        (59425.230, 5358.230),
+        (60779.827, 5073.827),
+        (62142.365, 4798.365),
+        (63518.708, 4536.708),
+        (64909.883, 4288.883),
+        (66308.037, 4049.037),
+        (67723.036, 3825.036),
+        (69144.100, 3608.100),
+        (70572.590, 3398.590),
+        (72011.162, 3198.162),
+        (73467.727, 3016.727),
+        (74927.029, 2837.029),
+        (76397.828, 2669.828),
+        (77878.042, 2512.042),
+        (79364.516, 2359.516),
+        (80854.287, 2211.287),
+        (82358.147, 2076.147),
+        (83867.019, 1947.019),
+        (85386.162, 1828.162),
+        (86906.700, 1709.700),
+        (88437.638, 1602.638),
+        (89985.871, 1511.871),
+        (91528.944, 1416.944),
+        (93069.134, 1319.134),
+        (94625.141, 1236.141),
+        (96185.549, 1158.549),
+        (97742.942, 1076.942),
+        (99305.986, 1001.986),
+        (100877.482, 935.482),
+        (102453.953, 872.953),
+        (104022.621, 803.621),
+        (105605.954, 747.954),
+        (107192.672, 696.672),
+        (108777.238, 643.238),
+        (110364.252, 591.252),
+        (111958.580, 547.580),
+        (113563.558, 513.558),
+        (115169.794, 481.794),
+        (116778.527, 452.527),
+        (118381.463, 416.463),
+        (119981.507, 378.507),
+        (121589.124, 347.124),
+        (123201.189, 321.189),
+        (124808.215, 290.215),
+        (126433.443, 276.443),
+        (128049.832, 254.832),
+        (129665.437, 231.437),
+        (131290.897, 218.897),
+        (132913.035, 203.035),
+        (134520.928, 171.928),
+        (136142.843, 155.843),
+        (137772.799, 146.799),
+        (139386.249, 122.249),
+        (141010.639, 108.639),
+        (142644.609, 103.609),
+        (144274.015, 95.015),
+        (145908.224, 90.224),
+        (147534.326, 78.326),
+        (149160.845, 66.845),
+        (150791.103, 58.103),
+        (152429.111, 58.111),
+        (154056.248, 46.248),
+        (155694.389, 46.389),
+        (157315.380, 29.380),
+        (158951.457, 26.457),
+        (160581.306, 18.306),
+        (162215.135, 13.135),
+        (163856.576, 16.576),
+    ],
+    &[
+        (47270.339, 47270.339),
+        (48865.603, 45588.603),
+        (50496.026, 43942.026),
+        (52164.937, 42334.937),
+        (53870.642, 40763.642),
+        (55617.117, 39233.117),
+        (57400.479, 37739.479),
+        (59221.194, 36283.194),
+        (61079.818, 34865.818),
+        (62973.737, 33482.737),
+        (64908.574, 32140.574),
+        (66878.034, 30833.034),
+        (68885.538, 29563.538),
+        (70926.377, 28328.377),
+        (73012.086, 27137.086),
+        (75131.913, 25979.913),
+        (77284.558, 24855.558),
+        (79472.373, 23766.373),
+        (81698.080, 22716.080),
+        (83956.397, 21697.397),
+        (86249.746, 20713.746),
+        (88571.757, 19758.757),
+        (90927.243, 18837.243),
+        (93314.749, 17948.749),
+        (95737.858, 17094.858),
+        (98192.563, 16272.563),
+        (100681.818, 15484.818),
+        (103194.293, 14720.293),
+        (105746.895, 13996.895),
+        (108321.742, 13294.742),
+        (110924.115, 12620.115),
+        (113554.404, 11973.404),
+        (116205.876, 11347.876),
+        (118893.506, 10759.506),
+        (121601.816, 10190.816),
+        (124332.761, 9644.761),
+        (127082.055, 9117.055),
+        (129867.436, 8625.436),
+        (132666.911, 8148.911),
+        (135489.665, 7694.665),
+        (138334.533, 7262.533),
+        (141199.072, 6850.072),
+        (144084.380, 6458.380),
+        (146985.378, 6083.378),
+        (149916.433, 5737.433),
+        (152857.626, 5401.626),
+        (155820.352, 5087.352),
+        (158792.242, 4782.242),
+        (161792.599, 4506.599),
+        (164794.626, 4231.626),
+        (167816.134, 3976.134),
+        (170857.182, 3740.182),
+        (173906.160, 3512.160),
+        (176953.545, 3283.545),
+        (180019.829, 3072.829),
+        (183103.850, 2879.850),
+        (186200.474, 2699.474),
+        (189307.381, 2529.381),
+        (192441.859, 2387.859),
+        (195549.894, 2218.894),
+        (198683.989, 2075.989),
+        (201833.208, 1948.208),
+        (204991.932, 1829.932),
+        (208145.858, 1707.858),
+        (211293.525, 1578.525),
+        (214475.927, 1483.927),
+        (217668.269, 1399.269),
+        (220847.391, 1301.391),
+        (224053.096, 1231.096),
+        (227251.907, 1152.907),
+        (230437.836, 1061.836),
+        (233630.671, 977.671),
+        (236831.269, 901.269),
+        (240037.630, 831.630),
+        (243249.100, 766.100),
+        (246452.533, 692.533),
+        (249660.528, 623.528),
+        (252906.231, 592.231),
+        (256135.346, 545.346),
+        (259365.211, 498.211),
+        (262612.467, 468.467),
+        (265851.922, 430.922),
+        (269094.480, 396.480),
+        (272340.559, 366.559),
+        (275577.665, 326.665),
+        (278823.246, 295.246),
+        (282085.393, 280.393),
+        (285341.541, 259.541),
+        (288584.073, 226.073),
+        (291822.757, 187.757),
+        (295081.967, 169.967),
+        (298337.039, 148.039),
+        (301619.055, 153.055),
+        (304899.750, 157.750),
+        (308168.191, 149.191),
+        (311435.923, 139.923),
+        (314700.064, 127.064),
+        (317943.496, 93.496),
+        (321201.920, 75.920),
+        (324454.009, 51.009),
+        (327730.795, 50.795),
+    ],
+    &[
+        (94541.455, 94541.455),
+        (97729.932, 91175.932),
+        (100993.629, 87886.629),
+        (104332.787, 84671.787),
+        (107744.944, 81530.944),
+        (111240.746, 78472.746),
+        (114807.805, 75485.805),
+        (118455.134, 72580.134),
+        (122171.318, 69742.318),
+        (125964.075, 66982.075),
+        (129834.059, 64298.059),
+        (133771.430, 61681.430),
+        (137792.218, 59149.218),
+        (141891.783, 56694.783),
+        (146048.304, 54298.304),
+        (150281.947, 51977.947),
+        (154593.796, 49735.796),
+        (158974.223, 47563.223),
+        (163423.691, 45458.691),
+        (167948.495, 43430.495),
+        (172534.572, 41462.572),
+        (177180.869, 39554.869),
+        (181906.914, 37727.914),
+        (186688.768, 35955.768),
+        (191532.418, 34246.418),
+        (196445.214, 32605.214),
+        (201414.980, 31020.980),
+        (206435.115, 29488.115),
+        (211522.568, 28021.568),
+        (216670.970, 26616.970),
+        (221869.771, 25261.771),
+        (227124.081, 23962.081),
+        (232436.418, 22721.418),
+        (237792.392, 21523.392),
+        (243212.288, 20390.288),
+        (248680.094, 19304.094),
+        (254177.513, 18247.513),
+        (259741.822, 17258.822),
+        (265345.684, 16308.684),
+        (271000.977, 15410.977),
+        (276675.092, 14531.092),
+        (282392.886, 13694.886),
+        (288165.290, 12914.290),
+        (293964.895, 12159.895),
+        (299805.277, 11447.277),
+        (305689.156, 10777.156),
+        (311585.922, 10119.922),
+        (317542.665, 9523.665),
+        (323524.019, 8951.019),
+        (329546.046, 8420.046),
+        (335593.585, 7913.585),
+        (341669.979, 7435.979),
+        (347758.893, 6971.893),
+        (353870.289, 6529.289),
+        (360035.090, 6141.090),
+        (366185.801, 5737.801),
+        (372383.606, 5381.606),
+        (378586.404, 5031.404),
+        (384790.308, 4681.308),
+        (391026.545, 4364.545),
+        (397301.565, 4085.565),
+        (403612.006, 3842.006),
+        (409914.841, 3591.841),
+        (416226.658, 3349.658),
+        (422550.450, 3120.450),
+        (428894.146, 2910.146),
+        (435262.712, 2724.712),
+        (441595.650, 2504.650),
+        (447957.799, 2312.799),
+        (454341.733, 2143.733),
+        (460774.421, 2022.421),
+        (467157.031, 1851.031),
+        (473584.846, 1725.846),
+        (480003.683, 1590.683),
+        (486418.872, 1452.872),
+        (492887.335, 1367.335),
+        (499357.336, 1283.336),
+        (505837.792, 1210.792),
+        (512347.093, 1166.093),
+        (518806.443, 1072.443),
+        (525283.046, 995.046),
+        (531767.385, 925.385),
+        (538289.345, 894.345),
+        (544816.739, 867.739),
+        (551318.930, 816.930),
+        (557819.113, 763.113),
+        (564341.100, 731.100),
+        (570883.028, 720.028),
+        (577363.598, 646.598),
+        (583925.476, 655.476),
+        (590460.237, 636.237),
+        (596999.003, 621.003),
+        (603473.171, 542.171),
+        (610012.523, 527.523),
+        (616545.803, 507.803),
+        (623074.274, 482.274),
+        (629592.029, 446.029),
+        (636135.117, 436.117),
+        (642619.884, 366.884),
+        (649138.975, 332.975),
+        (655671.921, 311.921),
+    ],
+    &[
+        (189083.689, 189083.689),
+        (195465.950, 182358.950),
+        (201997.990, 175783.990),
+        (208672.597, 169350.597),
+        (215502.259, 163073.259),
+        (222488.223, 156952.223),
+        (229620.358, 150977.358),
+        (236898.591, 145148.591),
+        (244340.462, 139482.462),
+        (251924.382, 133959.382),
+        (259653.310, 128581.310),
+        (267536.669, 123357.669),
+        (275567.946, 118281.946),
+        (283750.260, 113356.260),
+        (292070.101, 108569.101),
+        (300543.250, 103935.250),
+        (309154.971, 99439.971),
+        (317924.574, 95102.574),
+        (326802.380, 90872.380),
+        (335819.698, 86782.698),
+        (344995.882, 82851.882),
+        (354314.944, 79063.944),
+        (363743.171, 75385.171),
+        (373305.879, 71839.879),
+        (383011.644, 68438.644),
+        (392821.379, 65141.379),
+        (402795.177, 62008.177),
+        (412838.305, 58944.305),
+        (423027.497, 56025.497),
+        (433321.644, 53212.644),
+        (443717.101, 50501.101),
+        (454230.503, 47907.503),
+        (464813.553, 45383.553),
+        (475538.359, 43000.359),
+        (486360.022, 40715.022),
+        (497304.712, 38552.712),
+        (508330.712, 36471.712),
+        (519459.403, 34493.403),
+        (530697.378, 32623.378),
+        (541971.876, 30790.876),
+        (553316.014, 29028.014),
+        (564788.375, 27393.375),
+        (576287.030, 25785.030),
+        (587928.797, 24318.797),
+        (599640.754, 22923.754),
+        (611382.327, 21558.327),
+        (623165.239, 20234.239),
+        (635106.111, 19068.111),
+        (647081.325, 17935.325),
+        (659094.616, 16841.616),
+        (671169.035, 15809.035),
+        (683284.626, 14817.626),
+        (695503.720, 13929.720),
+        (707752.327, 13070.327),
+        (720028.939, 12239.939),
+        (732408.178, 11512.178),
+        (744789.235, 10786.235),
+        (757226.738, 10116.738),
+        (769726.259, 9508.259),
+        (782209.505, 8884.505),
+        (794721.962, 8289.962),
+        (807215.816, 7676.816),
+        (819753.680, 7107.680),
+        (832365.602, 6611.602),
+        (845073.279, 6212.279),
+        (857725.629, 5757.629),
+        (870467.675, 5392.675),
+        (883181.483, 4999.483),
+        (895898.116, 4608.116),
+        (908627.410, 4230.410),
+        (921442.116, 3938.116),
+        (934193.524, 3582.524),
+        (946975.398, 3257.398),
+        (959816.615, 2990.615),
+        (972682.661, 2749.661),
+        (985577.656, 2537.656),
+        (998459.379, 2312.379),
+        (1011409.559, 2155.559),
+        (1024386.328, 2024.328),
+        (1037295.422, 1826.422),
+        (1050206.988, 1630.988),
+        (1063070.323, 1387.323),
+        (1075978.691, 1188.691),
+        (1088962.876, 1064.876),
+        (1102005.081, 1000.081),
+        (1114967.653, 855.653),
+        (1127927.916, 708.916),
+        (1140976.696, 650.696),
+        (1153980.116, 546.116),
+        (1167014.157, 473.157),
+        (1180051.842, 403.842),
+        (1193095.120, 340.120),
+        (1206160.799, 298.799),
+        (1219181.546, 211.546),
+        (1232167.310, 90.310),
+        (1245259.239, 75.239),
+        (1258350.892, 59.892),
+        (1271382.713, -15.287),
+        (1284481.582, -24.418),
+        (1297567.012, -45.988),
+        (1310583.678, -136.322),
+    ],
+];
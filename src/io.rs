@@ -0,0 +1,665 @@
+//! Reading and writing sets and multisets as files, for experiment inputs and outputs.
+//!
+//! Three formats are supported:
+//!
+//! - Newline-delimited text: one element per line. For a multiset, every line is one copy, so an
+//!   element that occurs on `count` lines has multiplicity `count`.
+//! - CSV: `element` or `element,count` per line, the count defaults to 1. For a multiset, the
+//!   counts of an element that occurs on several lines are added.
+//! - Binary, with all integers little-endian:
+//!
+//! | bytes | content                                                 |
+//! |-------|---------------------------------------------------------|
+//! | 4     | magic `SMSS`                                            |
+//! | 1     | format version                                          |
+//! | 1     | kind, 0 for a set and 1 for a multiset                  |
+//! | 8     | record count                                            |
+//! | ...   | per record the element (8 bytes), and for a multiset    |
+//! |       | its count (8 bytes)                                     |
+//!
+//! In the text formats, surrounding whitespace and empty lines are ignored. The writers sort the
+//! elements, so that equal sets produce equal files. The readers are iterators, so large inputs
+//! can be streamed into e.g. `BloomFilter::from_elements` without building a `Set` first.
+use crate::multisets::Multiset;
+use crate::sets::Set;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+pub const MAGIC: [u8; 4] = *b"SMSS";
+pub const VERSION: u8 = 1;
+
+pub const KIND_SET: u8 = 0;
+pub const KIND_MULTISET: u8 = 1;
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    /// A line of a text file cannot be parsed. Lines are counted from 1.
+    MalformedLine {
+        line: usize,
+        content: String,
+        reason: &'static str,
+    },
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownKind(u8),
+    /// The input ends before the header or the records are complete.
+    Truncated,
+    /// There are bytes left after the records.
+    TrailingData,
+    /// The counts of this element add up to more than `usize::MAX`.
+    CountOverflow(usize),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(error) => error.fmt(f),
+            ReadError::MalformedLine {
+                line,
+                content,
+                reason,
+            } => write!(f, "line {}: {} in {:?}", line, reason, content),
+            ReadError::BadMagic => write!(f, "the input is not an encoded set or multiset"),
+            ReadError::UnsupportedVersion(version) => {
+                write!(f, "unsupported set format version {}", version)
+            }
+            ReadError::UnknownKind(kind) => write!(f, "unknown set kind {}", kind),
+            ReadError::Truncated => write!(f, "the encoded set is truncated"),
+            ReadError::TrailingData => write!(f, "unexpected data after the encoded set"),
+            ReadError::CountOverflow(element) => {
+                write!(f, "the counts of element {} overflow", element)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        ReadError::Io(error)
+    }
+}
+
+/// The non-empty lines of a text file together with their line numbers.
+struct NumberedLines<R> {
+    lines: io::Lines<R>,
+    line: usize,
+}
+
+impl<R: BufRead> Iterator for NumberedLines<R> {
+    type Item = Result<(usize, String), ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let content = match self.lines.next()? {
+                Ok(content) => content,
+                Err(error) => return Some(Err(error.into())),
+            };
+            self.line += 1;
+
+            if !content.trim().is_empty() {
+                return Some(Ok((self.line, content)));
+            }
+        }
+    }
+}
+
+fn parse_field(
+    field: &str,
+    line: usize,
+    content: &str,
+    reason: &'static str,
+) -> Result<usize, ReadError> {
+    field.trim().parse().map_err(|_| ReadError::MalformedLine {
+        line,
+        content: content.to_string(),
+        reason,
+    })
+}
+
+/// Streams the elements of a newline-delimited file, see `read_elements`.
+pub struct ElementLines<R> {
+    lines: NumberedLines<R>,
+}
+
+impl<R: BufRead> Iterator for ElementLines<R> {
+    type Item = Result<usize, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.lines.next()?.and_then(|(line, content)| {
+            parse_field(
+                &content,
+                line,
+                &content,
+                "the element is not an unsigned integer",
+            )
+        }))
+    }
+}
+
+/// Reads one element per line. Elements may repeat.
+pub fn read_elements<R: BufRead>(reader: R) -> ElementLines<R> {
+    ElementLines {
+        lines: NumberedLines {
+            lines: reader.lines(),
+            line: 0,
+        },
+    }
+}
+
+/// Streams the (element, count) records of a CSV file, see `read_csv`.
+pub struct CsvRecords<R> {
+    lines: NumberedLines<R>,
+}
+
+impl<R: BufRead> Iterator for CsvRecords<R> {
+    type Item = Result<(usize, usize), ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.lines.next()?.and_then(|(line, content)| {
+            let fields: Vec<&str> = content.split(',').collect();
+            let count = match fields.len() {
+                1 => 1,
+                2 => parse_field(
+                    fields[1],
+                    line,
+                    &content,
+                    "the count is not an unsigned integer",
+                )?,
+                _ => {
+                    return Err(ReadError::MalformedLine {
+                        line,
+                        content: content.clone(),
+                        reason: "expected an element and an optional count",
+                    })
+                }
+            };
+            let element = parse_field(
+                fields[0],
+                line,
+                &content,
+                "the element is not an unsigned integer",
+            )?;
+
+            Ok((element, count))
+        }))
+    }
+}
+
+/// Reads one `element` or `element,count` record per line, the count defaults to 1. Elements
+/// may repeat.
+pub fn read_csv<R: BufRead>(reader: R) -> CsvRecords<R> {
+    CsvRecords {
+        lines: NumberedLines {
+            lines: reader.lines(),
+            line: 0,
+        },
+    }
+}
+
+/// Streams the (element, count) records of a binary file, see `read_binary`.
+pub struct BinaryRecords<R> {
+    reader: R,
+    kind: u8,
+    remaining: u64,
+}
+
+impl<R: Read> BinaryRecords<R> {
+    /// `KIND_SET` or `KIND_MULTISET`.
+    pub fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ReadError> {
+        let mut bytes = [0; 8];
+        read_exact(&mut self.reader, &mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn read_record(&mut self) -> Result<(usize, usize), ReadError> {
+        let element = self.read_u64()? as usize;
+        let count = match self.kind {
+            KIND_SET => 1,
+            _ => self.read_u64()? as usize,
+        };
+
+        Ok((element, count))
+    }
+}
+
+impl<R: Read> Iterator for BinaryRecords<R> {
+    /// The count is 1 for the records of a set.
+    type Item = Result<(usize, usize), ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let record = self.read_record();
+        self.remaining = match record {
+            Ok(_) => self.remaining - 1,
+            Err(_) => 0,
+        };
+        if record.is_ok() && self.remaining == 0 {
+            // Only the end of the input may follow the last record
+            let mut byte = [0];
+            match self.reader.read(&mut byte) {
+                Ok(0) => {}
+                Ok(_) => return Some(Err(ReadError::TrailingData)),
+                Err(error) => return Some(Err(error.into())),
+            }
+        }
+
+        Some(record)
+    }
+}
+
+fn read_exact<R: Read>(reader: &mut R, bytes: &mut [u8]) -> Result<(), ReadError> {
+    reader
+        .read_exact(bytes)
+        .map_err(|error| match error.kind() {
+            io::ErrorKind::UnexpectedEof => ReadError::Truncated,
+            _ => error.into(),
+        })
+}
+
+/// Reads the header of a binary file and returns its records.
+pub fn read_binary<R: Read>(mut reader: R) -> Result<BinaryRecords<R>, ReadError> {
+    let mut header = [0; 14];
+    read_exact(&mut reader, &mut header)?;
+
+    if header[..4] != MAGIC {
+        return Err(ReadError::BadMagic);
+    }
+    if header[4] != VERSION {
+        return Err(ReadError::UnsupportedVersion(header[4]));
+    }
+    let kind = header[5];
+    if kind != KIND_SET && kind != KIND_MULTISET {
+        return Err(ReadError::UnknownKind(kind));
+    }
+
+    let mut records = BinaryRecords {
+        reader,
+        kind,
+        remaining: u64::from_le_bytes(header[6..].try_into().unwrap()),
+    };
+    if records.remaining == 0 {
+        let mut byte = [0];
+        if records.reader.read(&mut byte)? != 0 {
+            return Err(ReadError::TrailingData);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Writes one element per line.
+pub fn write_elements<W: Write, I: IntoIterator<Item = usize>>(
+    mut writer: W,
+    elements: I,
+) -> io::Result<()> {
+    for element in elements {
+        writeln!(writer, "{}", element)?;
+    }
+
+    Ok(())
+}
+
+/// Writes one `element,count` record per line.
+pub fn write_csv<W: Write, I: IntoIterator<Item = (usize, usize)>>(
+    mut writer: W,
+    element_counts: I,
+) -> io::Result<()> {
+    for (element, count) in element_counts {
+        writeln!(writer, "{},{}", element, count)?;
+    }
+
+    Ok(())
+}
+
+fn write_header<W: Write>(writer: &mut W, kind: u8, record_count: usize) -> io::Result<()> {
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&[VERSION, kind])?;
+    writer.write_all(&(record_count as u64).to_le_bytes())
+}
+
+impl Set {
+    fn sorted_elements(&self) -> Vec<usize> {
+        let mut elements: Vec<usize> = self.elements.iter().copied().collect();
+        elements.sort_unstable();
+        elements
+    }
+
+    /// See `read_elements`.
+    pub fn read_newline<R: BufRead>(reader: R) -> Result<Set, ReadError> {
+        read_elements(reader).collect()
+    }
+
+    pub fn write_newline<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_elements(writer, self.sorted_elements())
+    }
+
+    /// See `read_csv`, elements whose counts are all zero are left out.
+    pub fn read_csv<R: BufRead>(reader: R) -> Result<Set, ReadError> {
+        collect_set(read_csv(reader))
+    }
+
+    /// Writes every element with a count of 1.
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(
+            writer,
+            self.sorted_elements()
+                .into_iter()
+                .map(|element| (element, 1)),
+        )
+    }
+
+    /// Also accepts the encoding of a multiset, in which case the elements with a count of zero
+    /// are left out.
+    pub fn read_binary<R: Read>(reader: R) -> Result<Set, ReadError> {
+        collect_set(read_binary(reader)?)
+    }
+
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_header(&mut writer, KIND_SET, self.len())?;
+        for element in self.sorted_elements() {
+            writer.write_all(&(element as u64).to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn collect_set<I: Iterator<Item = Result<(usize, usize), ReadError>>>(
+    records: I,
+) -> Result<Set, ReadError> {
+    let mut set = Set::new(&[]);
+    for record in records {
+        let (element, count) = record?;
+        if count > 0 {
+            set.elements.insert(element);
+        }
+    }
+
+    Ok(set)
+}
+
+impl Multiset {
+    fn sorted_element_counts(&self) -> Vec<(usize, usize)> {
+        let mut element_counts: Vec<(usize, usize)> = self
+            .element_counts
+            .iter()
+            .map(|(element, count)| (*element, *count))
+            .collect();
+        element_counts.sort_unstable();
+        element_counts
+    }
+
+    /// See `read_elements`, every line is one copy of its element.
+    pub fn read_newline<R: BufRead>(reader: R) -> Result<Multiset, ReadError> {
+        collect_multiset(read_elements(reader).map(|element| element.map(|element| (element, 1))))
+    }
+
+    /// Writes every element once per copy.
+    pub fn write_newline<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_elements(
+            writer,
+            self.sorted_element_counts()
+                .into_iter()
+                .flat_map(|(element, count)| std::iter::repeat_n(element, count)),
+        )
+    }
+
+    /// See `read_csv`, the counts of repeated elements are added.
+    pub fn read_csv<R: BufRead>(reader: R) -> Result<Multiset, ReadError> {
+        collect_multiset(read_csv(reader))
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(writer, self.sorted_element_counts())
+    }
+
+    /// Also accepts the encoding of a set, in which case every count is 1.
+    pub fn read_binary<R: Read>(reader: R) -> Result<Multiset, ReadError> {
+        collect_multiset(read_binary(reader)?)
+    }
+
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_header(&mut writer, KIND_MULTISET, self.len())?;
+        for (element, count) in self.sorted_element_counts() {
+            writer.write_all(&(element as u64).to_le_bytes())?;
+            writer.write_all(&(count as u64).to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds the counts of repeated elements and leaves out elements with a total count of zero.
+fn collect_multiset<I: Iterator<Item = Result<(usize, usize), ReadError>>>(
+    records: I,
+) -> Result<Multiset, ReadError> {
+    let mut element_counts: HashMap<usize, usize> = HashMap::new();
+    for record in records {
+        let (element, count) = record?;
+        let total = element_counts.entry(element).or_insert(0);
+        *total = total
+            .checked_add(count)
+            .ok_or(ReadError::CountOverflow(element))?;
+    }
+    element_counts.retain(|_, count| *count > 0);
+
+    Ok(Multiset { element_counts })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{BloomFilter, Xxh3Hasher};
+    use crate::io::{read_binary, read_csv, read_elements, ReadError, KIND_MULTISET};
+    use crate::multisets::Multiset;
+    use crate::sets::Set;
+
+    type H = Xxh3Hasher;
+
+    fn assert_malformed(error: ReadError, expected_line: usize) {
+        match error {
+            ReadError::MalformedLine { line, .. } => assert_eq!(line, expected_line),
+            error => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_newline_round_trip() {
+        let set = Set::random(100, 10_000);
+        let mut bytes = Vec::new();
+        set.write_newline(&mut bytes).unwrap();
+        assert_eq!(Set::read_newline(&bytes[..]).unwrap(), set);
+
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let mut bytes = Vec::new();
+        multiset.write_newline(&mut bytes).unwrap();
+        assert_eq!(bytes, b"1\n3\n3\n4\n");
+        assert_eq!(Multiset::read_newline(&bytes[..]).unwrap(), multiset);
+    }
+
+    #[test]
+    fn test_read_newline() {
+        let input = "3\n\n 1 \r\n3\n";
+        assert_eq!(
+            Set::read_newline(input.as_bytes()).unwrap(),
+            Set::new(&[1, 3])
+        );
+        assert_eq!(
+            Multiset::read_newline(input.as_bytes()).unwrap(),
+            Multiset::new(&[1, 3], &[1, 2])
+        );
+
+        assert_malformed(Set::read_newline("1\n\n2\nx\n".as_bytes()).unwrap_err(), 4);
+        assert_malformed(Set::read_newline("-1\n".as_bytes()).unwrap_err(), 1);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let multiset = Multiset::random(100, 10_000, 9);
+        let mut bytes = Vec::new();
+        multiset.write_csv(&mut bytes).unwrap();
+        assert_eq!(Multiset::read_csv(&bytes[..]).unwrap(), multiset);
+
+        let set = Set::new(&[4, 1]);
+        let mut bytes = Vec::new();
+        set.write_csv(&mut bytes).unwrap();
+        assert_eq!(bytes, b"1,1\n4,1\n");
+        assert_eq!(Set::read_csv(&bytes[..]).unwrap(), set);
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let input = "1\n3, 2\n5,0\n3,1\n";
+        assert_eq!(
+            Multiset::read_csv(input.as_bytes()).unwrap(),
+            Multiset::new(&[1, 3], &[1, 3])
+        );
+        assert_eq!(Set::read_csv(input.as_bytes()).unwrap(), Set::new(&[1, 3]));
+
+        assert_malformed(Multiset::read_csv("1,2\n3,x\n".as_bytes()).unwrap_err(), 2);
+        assert_malformed(Multiset::read_csv("1,2,3\n".as_bytes()).unwrap_err(), 1);
+        assert_malformed(Multiset::read_csv(",2\n".as_bytes()).unwrap_err(), 1);
+
+        let overflow = format!("3,{}\n3,1\n", usize::MAX);
+        assert!(matches!(
+            Multiset::read_csv(overflow.as_bytes()),
+            Err(ReadError::CountOverflow(3))
+        ));
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let set = Set::random(100, 10_000);
+        let mut bytes = Vec::new();
+        set.write_binary(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 14 + 8 * 100);
+        assert_eq!(Set::read_binary(&bytes[..]).unwrap(), set);
+
+        let multiset = Multiset::random(100, 10_000, 9);
+        let mut bytes = Vec::new();
+        multiset.write_binary(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 14 + 16 * 100);
+        assert_eq!(read_binary(&bytes[..]).unwrap().kind(), KIND_MULTISET);
+        assert_eq!(Multiset::read_binary(&bytes[..]).unwrap(), multiset);
+
+        let mut bytes = Vec::new();
+        Set::new(&[]).write_binary(&mut bytes).unwrap();
+        assert!(Set::read_binary(&bytes[..]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_binary_layout() {
+        let mut bytes = Vec::new();
+        Multiset::new(&[2], &[5]).write_binary(&mut bytes).unwrap();
+
+        let mut expected = b"SMSS\x01\x01".to_vec();
+        expected.extend_from_slice(&1u64.to_le_bytes());
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&5u64.to_le_bytes());
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_read_binary_invalid() {
+        let mut bytes = Vec::new();
+        Set::new(&[1, 3, 4]).write_binary(&mut bytes).unwrap();
+
+        assert!(matches!(
+            Set::read_binary(&bytes[..bytes.len() - 1]),
+            Err(ReadError::Truncated)
+        ));
+        assert!(matches!(
+            Set::read_binary(&bytes[..10]),
+            Err(ReadError::Truncated)
+        ));
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            Set::read_binary(&trailing[..]),
+            Err(ReadError::TrailingData)
+        ));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(
+            Set::read_binary(&bad_magic[..]),
+            Err(ReadError::BadMagic)
+        ));
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 2;
+        assert!(matches!(
+            Set::read_binary(&bad_version[..]),
+            Err(ReadError::UnsupportedVersion(2))
+        ));
+
+        let mut bad_kind = bytes;
+        bad_kind[5] = 7;
+        assert!(matches!(
+            Set::read_binary(&bad_kind[..]),
+            Err(ReadError::UnknownKind(7))
+        ));
+    }
+
+    #[test]
+    fn test_stream_into_bloom_filter() {
+        let set = Set::random(100, 10_000);
+        let mut bytes = Vec::new();
+        set.write_newline(&mut bytes).unwrap();
+
+        let elements = read_elements(&bytes[..]).map(Result::unwrap);
+        assert_eq!(
+            BloomFilter::from_elements::<H, _>(elements, 1000, 3),
+            BloomFilter::from_set::<H>(&set, 1000, 3)
+        );
+
+        let records: Vec<(usize, usize)> = read_csv("7\n8,2\n".as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records, vec![(7, 1), (8, 2)]);
+    }
+
+    #[test]
+    fn test_stream_csv_into_bloom_filter() {
+        // Repeated elements are summed by both the streaming constructor and the reader
+        let csv = "1,2\n3\n1\n4,3\n3,2\n5,0\n";
+        let element_counts = read_csv(csv.as_bytes()).map(Result::unwrap);
+        assert_eq!(
+            BloomFilter::from_element_counts::<H, _>(element_counts, 1000, 3, 5),
+            BloomFilter::from_multiset::<H>(
+                &Multiset::read_csv(csv.as_bytes()).unwrap(),
+                1000,
+                3,
+                5
+            )
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error = Set::read_newline("1\nabc\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: the element is not an unsigned integer in \"abc\""
+        );
+    }
+}
@@ -1,3 +1,22 @@
+pub mod blocked_bloom_filters;
 pub mod bloom_filters;
+pub mod bottom_k;
+pub mod compression;
+pub mod counting_bloom_filters;
+pub mod deletable_bloom_filters;
+pub mod distributions;
+pub mod error;
+pub mod golomb_coded_sets;
+pub mod hyperloglog;
+mod hyperloglog_bias;
+pub mod io;
+pub mod minhash;
 pub mod multisets;
+pub mod overlaps;
+pub mod packing;
+pub mod partitioned_bloom_filters;
+pub mod protocols;
+pub mod scalable_bloom_filters;
 pub mod sets;
+pub mod spectral_bloom_filters;
+pub mod wire_format;
@@ -0,0 +1,320 @@
+use crate::bloom_filters::ElementHasher;
+use crate::multisets::Multiset;
+use crate::sets::Set;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A MinHash signature. Only signatures built with the same method, hasher and length can be
+/// compared.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MinHash {
+    pub values: Vec<u64>,
+    pub method: MinHashMethod,
+    pub hasher_id: u8,
+}
+
+/// How the values of a `MinHash` signature are computed.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MinHashMethod {
+    /// One minimum per differently seeded hash, see `MinHash::from_elements`.
+    KHashes,
+    /// One minimum per bin of a single hash, see `MinHash::from_elements_one_permutation`.
+    OnePermutation,
+}
+
+impl MinHash {
+    /// The fraction of equal values, an unbiased estimate of the Jaccard similarity of the
+    /// underlying sets. Panics if the signatures differ in their method, hasher or length.
+    pub fn estimate_jaccard(&self, other: &MinHash) -> f64 {
+        assert_eq!(self.method, other.method);
+        assert_eq!(self.hasher_id, other.hasher_id);
+        assert_eq!(self.values.len(), other.values.len());
+
+        let equal_count = self
+            .values
+            .iter()
+            .zip(&other.values)
+            .filter(|(a, b)| a == b)
+            .count();
+
+        equal_count as f64 / self.values.len() as f64
+    }
+}
+
+impl MinHash {
+    /// Builds a signature from the minimum hash of the elements under `k` differently seeded
+    /// hashes. The elements may contain duplicates. Panics if `k` is 0.
+    pub fn from_elements<H: ElementHasher, I: IntoIterator<Item = usize>>(
+        k: usize,
+        elements: I,
+    ) -> Self {
+        assert!(k > 0, "the signature length must be at least 1");
+
+        let mut values = vec![u64::MAX; k];
+        let seeds: Vec<u64> = (0..k as u64).collect();
+
+        for element in elements {
+            for (value, hash) in values
+                .iter_mut()
+                .zip(H::hash_element_multiple_seeds(&element, &seeds))
+            {
+                *value = (*value).min(hash as u64);
+            }
+        }
+
+        MinHash {
+            values,
+            method: MinHashMethod::KHashes,
+            hasher_id: H::ID,
+        }
+    }
+
+    /// Builds a signature using one-permutation hashing: a single hash splits the elements over
+    /// `k` bins and each bin keeps its minimum. Empty bins are filled by rotation densification,
+    /// borrowing the value of the next non-empty bin shifted by the distance to it. The elements
+    /// may contain duplicates. Panics if `k` is 0.
+    pub fn from_elements_one_permutation<H: ElementHasher, I: IntoIterator<Item = usize>>(
+        k: usize,
+        elements: I,
+    ) -> Self {
+        assert!(k > 0, "the signature length must be at least 1");
+
+        let mut bins: Vec<Option<u64>> = vec![None; k];
+
+        for element in elements {
+            let hash = H::hash_element(&element, 0) as u64;
+            let bin = &mut bins[(hash % k as u64) as usize];
+            let value = hash / k as u64;
+
+            *bin = Some(bin.map_or(value, |current| current.min(value)));
+        }
+
+        let offset = u64::MAX / k as u64 + 1;
+        let values = (0..k)
+            .map(|i| {
+                (0..k)
+                    .find_map(|distance| {
+                        bins[(i + distance) % k]
+                            .map(|value| value.wrapping_add((distance as u64).wrapping_mul(offset)))
+                    })
+                    .unwrap_or(u64::MAX)
+            })
+            .collect();
+
+        MinHash {
+            values,
+            method: MinHashMethod::OnePermutation,
+            hasher_id: H::ID,
+        }
+    }
+}
+
+impl Set {
+    /// See `MinHash::from_elements`.
+    pub fn to_minhash<H: ElementHasher>(&self, k: usize) -> MinHash {
+        MinHash::from_elements::<H, _>(k, self.elements.iter().copied())
+    }
+
+    /// See `MinHash::from_elements_one_permutation`.
+    pub fn to_one_permutation_minhash<H: ElementHasher>(&self, k: usize) -> MinHash {
+        MinHash::from_elements_one_permutation::<H, _>(k, self.elements.iter().copied())
+    }
+}
+
+/// A weighted MinHash signature of a multiset, every sample is an element and a quantized
+/// count. Only signatures built with the same hasher and length can be compared.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WeightedMinHash {
+    pub samples: Vec<(usize, i64)>,
+    pub hasher_id: u8,
+}
+
+impl WeightedMinHash {
+    /// The fraction of equal samples, an unbiased estimate of the weighted Jaccard similarity of
+    /// the underlying multisets. Panics if the signatures differ in their hasher or length.
+    pub fn estimate_weighted_jaccard(&self, other: &WeightedMinHash) -> f64 {
+        assert_eq!(self.hasher_id, other.hasher_id);
+        assert_eq!(self.samples.len(), other.samples.len());
+
+        let equal_count = self
+            .samples
+            .iter()
+            .zip(&other.samples)
+            .filter(|(a, b)| a == b)
+            .count();
+
+        equal_count as f64 / self.samples.len() as f64
+    }
+}
+
+impl WeightedMinHash {
+    /// Builds a signature of `k` samples using Ioffe's improved consistent weighted sampling
+    /// (ICWS), deriving the random variables of every element from its hashes. Every element
+    /// must occur in at most one pair. Panics if `k` is 0.
+    pub fn from_element_counts<H: ElementHasher, I: IntoIterator<Item = (usize, usize)>>(
+        k: usize,
+        element_counts: I,
+    ) -> Self {
+        assert!(k > 0, "the signature length must be at least 1");
+
+        let mut samples = vec![(0, 0); k];
+        let mut minima = vec![f64::INFINITY; k];
+        let seeds: Vec<u64> = (0..5 * k as u64).collect();
+
+        for (element, count) in element_counts {
+            if count == 0 {
+                continue;
+            }
+
+            let log_weight = (count as f64).ln();
+            let uniforms: Vec<f64> = H::hash_element_multiple_seeds(&element, &seeds)
+                .into_iter()
+                .map(|hash| to_unit_interval(hash as u64))
+                .collect();
+
+            for (i, u) in uniforms.chunks(5).enumerate() {
+                let r = -(u[0] * u[1]).ln();
+                let c = -(u[2] * u[3]).ln();
+                let beta = u[4];
+
+                let t = (log_weight / r + beta).floor();
+                let y = (r * (t - beta)).exp();
+                let a = c / (y * r.exp());
+
+                if a < minima[i] {
+                    minima[i] = a;
+                    samples[i] = (element, t as i64);
+                }
+            }
+        }
+
+        WeightedMinHash {
+            samples,
+            hasher_id: H::ID,
+        }
+    }
+}
+
+impl Multiset {
+    /// See `WeightedMinHash::from_element_counts`.
+    pub fn to_weighted_minhash<H: ElementHasher>(&self, k: usize) -> WeightedMinHash {
+        WeightedMinHash::from_element_counts::<H, _>(
+            k,
+            self.element_counts
+                .iter()
+                .map(|(element, count)| (*element, *count)),
+        )
+    }
+}
+
+/// Maps a hash to a uniform value in the open interval (0, 1).
+fn to_unit_interval(hash: u64) -> f64 {
+    ((hash >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::Xxh3Hasher;
+    use crate::minhash::MinHash;
+    use crate::multisets::Multiset;
+    use crate::sets::Set;
+
+    type H = Xxh3Hasher;
+
+    fn overlapping_sets() -> (Set, Set) {
+        // Jaccard similarity 500 / 1500
+        ((0..1000).collect::<Set>(), (500..2000).collect::<Set>())
+    }
+
+    #[test]
+    fn test_minhash() {
+        let (set1, set2) = overlapping_sets();
+        let estimate = set1
+            .to_minhash::<H>(512)
+            .estimate_jaccard(&set2.to_minhash::<H>(512));
+
+        assert!((estimate - set1.jaccard(&set2)).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_one_permutation_minhash() {
+        let (set1, set2) = overlapping_sets();
+        let estimate = set1
+            .to_one_permutation_minhash::<H>(512)
+            .estimate_jaccard(&set2.to_one_permutation_minhash::<H>(512));
+
+        assert!((estimate - set1.jaccard(&set2)).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_one_permutation_minhash_densification() {
+        // Far fewer elements than bins, so most bins are filled by densification
+        let set1 = Set::new(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        let set2 = Set::new(&[5, 6, 7, 8, 9, 10, 11, 12]);
+
+        let minhash1 = set1.to_one_permutation_minhash::<H>(128);
+        assert!(minhash1.values.iter().all(|value| *value != u64::MAX));
+        assert_eq!(minhash1.estimate_jaccard(&minhash1), 1.);
+
+        let estimate = minhash1.estimate_jaccard(&set2.to_one_permutation_minhash::<H>(128));
+        assert!(estimate > 0. && estimate < 1.);
+    }
+
+    #[test]
+    fn test_minhash_from_elements() {
+        // The stream can contain duplicates and need not be collected into a set
+        let elements = (0..1000).chain(0..500);
+        let set: Set = (0..1000).collect();
+
+        assert_eq!(
+            MinHash::from_elements::<H, _>(64, elements.clone()),
+            set.to_minhash::<H>(64)
+        );
+        assert_eq!(
+            MinHash::from_elements_one_permutation::<H, _>(64, elements),
+            set.to_one_permutation_minhash::<H>(64)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the signature length must be at least 1")]
+    fn test_zero_length() {
+        Set::new(&[1, 2, 3]).to_one_permutation_minhash::<H>(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_different_methods() {
+        let set = Set::new(&[1, 2, 3]);
+        set.to_minhash::<H>(64)
+            .estimate_jaccard(&set.to_one_permutation_minhash::<H>(64));
+    }
+
+    #[test]
+    fn test_weighted_minhash() {
+        let multiset1: Multiset = (0..400).map(|element| (element, element % 5 + 1)).collect();
+        let multiset2: Multiset = (200..600)
+            .map(|element| (element, element % 3 + 1))
+            .collect();
+
+        let estimate = multiset1
+            .to_weighted_minhash::<H>(512)
+            .estimate_weighted_jaccard(&multiset2.to_weighted_minhash::<H>(512));
+
+        assert!((estimate - multiset1.weighted_jaccard(&multiset2)).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_weighted_minhash_counts_matter() {
+        let multiset1 = Multiset::new(&[1, 2, 3], &[1, 1, 1]);
+        let multiset2 = Multiset::new(&[1, 2, 3], &[9, 9, 9]);
+
+        let minhash1 = multiset1.to_weighted_minhash::<H>(128);
+        assert_eq!(minhash1.estimate_weighted_jaccard(&minhash1), 1.);
+        assert!(minhash1.estimate_weighted_jaccard(&multiset2.to_weighted_minhash::<H>(128)) < 0.5);
+    }
+}
@@ -1,9 +1,14 @@
+use crate::error::Error;
 use rand::rngs::OsRng;
 use rand::seq::index::sample;
 use rand::Rng;
+use std::cmp;
 use std::collections::HashMap;
 use std::iter::FromIterator;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 impl Multiset {
     pub fn new(elements: &[usize], counts: &[usize]) -> Self {
         assert_eq!(elements.len(), counts.len());
@@ -19,13 +24,42 @@ impl Multiset {
 
     /// `max_multiplicity` is inclusive, so `max_multiplicity = 5` will generate counts that are
     /// uniformly chosen from 1, 2, 3, 4, 5.
+    /// Panics if `max_multiplicity` is zero or `element_count` exceeds `universe`.
     pub fn random(element_count: usize, universe: usize, max_multiplicity: usize) -> Self {
-        let elements = sample(&mut OsRng, universe, element_count).into_iter();
-        let counts = (0..element_count).map(|_| OsRng.gen_range(1..=max_multiplicity));
+        Multiset::try_random(element_count, universe, max_multiplicity)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        Multiset {
-            element_counts: elements.zip(counts).collect(),
+    pub fn try_random(
+        element_count: usize,
+        universe: usize,
+        max_multiplicity: usize,
+    ) -> Result<Self, Error> {
+        Multiset::try_random_with_rng(&mut OsRng, element_count, universe, max_multiplicity)
+    }
+
+    /// Same as `try_random`, but draws from `rng`, e.g. a seeded one for reproducible multisets.
+    pub fn try_random_with_rng<R: Rng + ?Sized>(
+        rng: &mut R,
+        element_count: usize,
+        universe: usize,
+        max_multiplicity: usize,
+    ) -> Result<Self, Error> {
+        if max_multiplicity == 0 {
+            return Err(Error::InvalidParameter(
+                "the maximum multiplicity must be at least 1",
+            ));
         }
+        if element_count > universe {
+            return Err(Error::Infeasible("the element count exceeds the universe"));
+        }
+
+        let elements = sample(rng, universe, element_count).into_iter();
+        let counts = (0..element_count).map(|_| rng.gen_range(1..=max_multiplicity));
+
+        Ok(Multiset {
+            element_counts: elements.zip(counts).collect(),
+        })
     }
 
     pub fn len(&self) -> usize {
@@ -36,20 +70,241 @@ impl Multiset {
         self.element_counts.is_empty()
     }
 
-    pub fn to_bitset(&self, universe: usize, max_multiplicity: usize) -> Vec<bool> {
-        let mut bitset = vec![false; universe * max_multiplicity];
+    /// The generalized Jaccard similarity: the sum of the minimum counts over the sum of the
+    /// maximum counts of all elements, or 1 if both multisets are empty.
+    pub fn weighted_jaccard(&self, other: &Multiset) -> f64 {
+        let mut min_sum = 0;
+        let mut max_sum = 0;
 
         for (element, count) in &self.element_counts {
-            for i in 0..*count {
-                bitset[*element * max_multiplicity + i] = true;
+            let other_count = other.element_counts.get(element).copied().unwrap_or(0);
+            min_sum += cmp::min(*count, other_count);
+            max_sum += cmp::max(*count, other_count);
+        }
+        for (element, count) in &other.element_counts {
+            if !self.element_counts.contains_key(element) {
+                max_sum += count;
             }
         }
 
-        bitset
+        if max_sum == 0 {
+            return 1.;
+        }
+
+        min_sum as f64 / max_sum as f64
+    }
+
+    /// Panics under the same conditions as `to_bitset_with_encoding`.
+    pub fn to_bitset(&self, universe: usize, max_multiplicity: usize) -> Vec<bool> {
+        self.to_bitset_with_encoding(universe, max_multiplicity, MultiplicityEncoding::Unary)
+    }
+
+    /// Inverse of `to_bitset`, elements with a count of zero are left out. Panics under the same
+    /// conditions as `from_bitset_with_encoding`.
+    pub fn from_bitset(bitset: &[bool], max_multiplicity: usize) -> Multiset {
+        Multiset::from_bitset_with_encoding(bitset, max_multiplicity, MultiplicityEncoding::Unary)
+    }
+
+    /// Writes each element's count into its own block of `encoding.bits_per_element(max_multiplicity)` bits.
+    /// Panics if `max_multiplicity` is zero, a count exceeds it, an element is not below
+    /// `universe`, or the bitset length overflows.
+    pub fn to_bitset_with_encoding(
+        &self,
+        universe: usize,
+        max_multiplicity: usize,
+        encoding: MultiplicityEncoding,
+    ) -> Vec<bool> {
+        self.try_to_bitset_with_encoding(universe, max_multiplicity, encoding)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_to_bitset_with_encoding(
+        &self,
+        universe: usize,
+        max_multiplicity: usize,
+        encoding: MultiplicityEncoding,
+    ) -> Result<Vec<bool>, Error> {
+        try_bitset_from_element_counts(
+            self.element_counts
+                .iter()
+                .map(|(element, count)| (*element, *count)),
+            universe,
+            max_multiplicity,
+            encoding,
+        )
+    }
+
+    /// Inverse of `to_bitset_with_encoding`, elements with a count of zero are left out. Panics
+    /// if `max_multiplicity` is zero, the bitset does not consist of whole blocks, a block is not
+    /// a valid code of `encoding`, or a block holds a count above `max_multiplicity`.
+    pub fn from_bitset_with_encoding(
+        bitset: &[bool],
+        max_multiplicity: usize,
+        encoding: MultiplicityEncoding,
+    ) -> Multiset {
+        Multiset::try_from_bitset_with_encoding(bitset, max_multiplicity, encoding)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_from_bitset_with_encoding(
+        bitset: &[bool],
+        max_multiplicity: usize,
+        encoding: MultiplicityEncoding,
+    ) -> Result<Multiset, Error> {
+        check_max_multiplicity(max_multiplicity)?;
+        let width = encoding.bits_per_element(max_multiplicity);
+        if bitset.len() % width != 0 {
+            return Err(Error::InvalidParameter(
+                "the bitset length is not a multiple of the bits per element",
+            ));
+        }
+
+        let mut element_counts: Vec<(usize, usize)> = Vec::new();
+        for (element, block) in bitset.chunks(width).enumerate() {
+            let count = match encoding {
+                MultiplicityEncoding::Unary => {
+                    let count = block.iter().take_while(|b| **b).count();
+                    if block[count..].iter().any(|b| *b) {
+                        return Err(Error::InvalidParameter(
+                            "a unary block has a set bit after an unset one",
+                        ));
+                    }
+                    count
+                }
+                MultiplicityEncoding::Binary => block
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| **b)
+                    .map(|(i, _)| 1 << i)
+                    .sum(),
+                MultiplicityEncoding::OneHot => {
+                    if block.iter().filter(|b| **b).count() > 1 {
+                        return Err(Error::InvalidParameter(
+                            "a one-hot block has more than one set bit",
+                        ));
+                    }
+                    block
+                        .iter()
+                        .position(|b| *b)
+                        .map_or(0, |position| position + 1)
+                }
+            };
+
+            if count > 0 {
+                element_counts.push((element, count));
+            }
+        }
+
+        if element_counts
+            .iter()
+            .any(|(_, count)| *count > max_multiplicity)
+        {
+            return Err(Error::InvalidParameter(
+                "a count exceeds the maximum multiplicity",
+            ));
+        }
+
+        Ok(element_counts.into_iter().collect())
+    }
+}
+
+fn check_max_multiplicity(max_multiplicity: usize) -> Result<(), Error> {
+    if max_multiplicity == 0 {
+        return Err(Error::InvalidParameter(
+            "the maximum multiplicity must be at least 1",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Builds the same bitset as `Multiset::to_bitset_with_encoding` from a stream of (element,
+/// count) pairs without collecting them first. Every element must occur in at most one pair.
+/// Panics under the same conditions as `Multiset::to_bitset_with_encoding`.
+pub fn bitset_from_element_counts<I: IntoIterator<Item = (usize, usize)>>(
+    element_counts: I,
+    universe: usize,
+    max_multiplicity: usize,
+    encoding: MultiplicityEncoding,
+) -> Vec<bool> {
+    try_bitset_from_element_counts(element_counts, universe, max_multiplicity, encoding)
+        .unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn try_bitset_from_element_counts<I: IntoIterator<Item = (usize, usize)>>(
+    element_counts: I,
+    universe: usize,
+    max_multiplicity: usize,
+    encoding: MultiplicityEncoding,
+) -> Result<Vec<bool>, Error> {
+    check_max_multiplicity(max_multiplicity)?;
+    let width = encoding.bits_per_element(max_multiplicity);
+    let bit_count = universe
+        .checked_mul(width)
+        .ok_or(Error::InvalidParameter("the bitset length overflows"))?;
+    let mut bitset = vec![false; bit_count];
+
+    for (element, count) in element_counts {
+        if count > max_multiplicity {
+            return Err(Error::InvalidParameter(
+                "a count exceeds the maximum multiplicity",
+            ));
+        }
+        if element >= universe {
+            return Err(Error::InvalidParameter(
+                "an element is not below the universe",
+            ));
+        }
+
+        let block = &mut bitset[element * width..(element + 1) * width];
+
+        match encoding {
+            MultiplicityEncoding::Unary => {
+                for bit in block.iter_mut().take(count) {
+                    *bit = true;
+                }
+            }
+            MultiplicityEncoding::Binary => {
+                for (i, bit) in block.iter_mut().enumerate() {
+                    *bit = (count >> i) & 1 == 1;
+                }
+            }
+            MultiplicityEncoding::OneHot => {
+                if count > 0 {
+                    block[count - 1] = true;
+                }
+            }
+        }
+    }
+
+    Ok(bitset)
+}
+
+/// How the multiplicity of an element is written into a multiset bitset.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum MultiplicityEncoding {
+    /// `count` ones followed by zeros (thermometer code), `max_multiplicity` bits per element.
+    Unary,
+    /// The count in binary with the least significant bit first, using just enough bits to
+    /// represent `max_multiplicity`.
+    Binary,
+    /// A single one at position `count - 1`, `max_multiplicity` bits per element.
+    OneHot,
+}
+
+impl MultiplicityEncoding {
+    pub fn bits_per_element(&self, max_multiplicity: usize) -> usize {
+        match self {
+            MultiplicityEncoding::Unary | MultiplicityEncoding::OneHot => max_multiplicity,
+            MultiplicityEncoding::Binary => {
+                (usize::BITS - max_multiplicity.leading_zeros()) as usize
+            }
+        }
     }
 }
 
 #[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Multiset {
     pub element_counts: HashMap<usize, usize>,
 }
@@ -63,8 +318,12 @@ impl FromIterator<(usize, usize)> for Multiset {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
-    use crate::multisets::Multiset;
+    use crate::error::Error;
+    use crate::multisets::{bitset_from_element_counts, MultiplicityEncoding, Multiset};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     #[test]
     fn test_random() {
@@ -88,6 +347,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_try_random() {
+        assert!(matches!(
+            Multiset::try_random(5, 100, 0),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            Multiset::try_random(101, 100, 10),
+            Err(Error::Infeasible(_))
+        ));
+    }
+
+    #[test]
+    fn test_random_with_rng() {
+        let multiset1 = Multiset::try_random_with_rng(&mut StdRng::seed_from_u64(7), 20, 100, 5);
+        let multiset2 = Multiset::try_random_with_rng(&mut StdRng::seed_from_u64(7), 20, 100, 5);
+        assert_eq!(multiset1.unwrap(), multiset2.unwrap());
+    }
+
     #[test]
     fn test_multiset_from_iter() {
         let elements = vec![1usize, 3, 4];
@@ -99,6 +377,23 @@ mod tests {
         assert_eq!(multiset_a, multiset_b);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let json = serde_json::to_string(&multiset).unwrap();
+        assert_eq!(serde_json::from_str::<Multiset>(&json).unwrap(), multiset);
+    }
+
+    #[test]
+    fn test_weighted_jaccard() {
+        let multiset1 = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let multiset2 = Multiset::new(&[1, 3, 5], &[3, 1, 2]);
+
+        assert_eq!(multiset1.weighted_jaccard(&multiset2), 2. / 8.);
+        assert_eq!(multiset1.weighted_jaccard(&multiset1), 1.);
+    }
+
     #[test]
     fn test_to_bitset() {
         let multiset = Multiset::new(&vec![1, 3, 4], &vec![1, 2, 1]);
@@ -107,4 +402,136 @@ mod tests {
             vec![false, false, true, false, false, false, true, true, true, false]
         );
     }
+
+    #[test]
+    fn test_bitset_from_element_counts() {
+        assert_eq!(
+            bitset_from_element_counts(
+                vec![(4, 1), (1, 1), (3, 2)],
+                5,
+                2,
+                MultiplicityEncoding::Unary
+            ),
+            Multiset::new(&[1, 3, 4], &[1, 2, 1]).to_bitset(5, 2)
+        );
+    }
+
+    #[test]
+    fn test_from_bitset() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        assert_eq!(
+            Multiset::from_bitset(&multiset.to_bitset(5, 2), 2),
+            multiset
+        );
+    }
+
+    #[test]
+    fn test_to_bitset_binary() {
+        let multiset = Multiset::new(&[0, 2], &[5, 2]);
+        assert_eq!(
+            multiset.to_bitset_with_encoding(3, 5, MultiplicityEncoding::Binary),
+            vec![true, false, true, false, false, false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn test_to_bitset_one_hot() {
+        let multiset = Multiset::new(&[0, 2], &[3, 1]);
+        assert_eq!(
+            multiset.to_bitset_with_encoding(3, 3, MultiplicityEncoding::OneHot),
+            vec![false, false, true, false, false, false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_bitset_round_trip() {
+        let multiset = Multiset::random(20, 100, 9);
+
+        for encoding in [
+            MultiplicityEncoding::Unary,
+            MultiplicityEncoding::Binary,
+            MultiplicityEncoding::OneHot,
+        ] {
+            let bitset = multiset.to_bitset_with_encoding(100, 9, encoding);
+            assert_eq!(bitset.len(), 100 * encoding.bits_per_element(9));
+            assert_eq!(
+                Multiset::from_bitset_with_encoding(&bitset, 9, encoding),
+                multiset
+            );
+        }
+    }
+
+    #[test]
+    fn test_bitset_invalid_parameters() {
+        let multiset = Multiset::new(&[1, 3], &[1, 4]);
+        for encoding in [
+            MultiplicityEncoding::Unary,
+            MultiplicityEncoding::Binary,
+            MultiplicityEncoding::OneHot,
+        ] {
+            assert_eq!(
+                multiset.try_to_bitset_with_encoding(5, 3, encoding),
+                Err(Error::InvalidParameter(
+                    "a count exceeds the maximum multiplicity"
+                ))
+            );
+            assert_eq!(
+                multiset.try_to_bitset_with_encoding(3, 4, encoding),
+                Err(Error::InvalidParameter(
+                    "an element is not below the universe"
+                ))
+            );
+            assert_eq!(
+                Multiset::try_from_bitset_with_encoding(&[], 0, encoding),
+                Err(Error::InvalidParameter(
+                    "the maximum multiplicity must be at least 1"
+                ))
+            );
+            assert_eq!(
+                Multiset::try_from_bitset_with_encoding(&[true; 7], 3, encoding),
+                Err(Error::InvalidParameter(
+                    "the bitset length is not a multiple of the bits per element"
+                ))
+            );
+        }
+
+        // Three binary bits can hold a 7, which exceeds a maximum multiplicity of 5
+        assert_eq!(
+            Multiset::try_from_bitset_with_encoding(&[true; 3], 5, MultiplicityEncoding::Binary),
+            Err(Error::InvalidParameter(
+                "a count exceeds the maximum multiplicity"
+            ))
+        );
+
+        assert_eq!(
+            Multiset::try_from_bitset_with_encoding(
+                &[true, false, true],
+                3,
+                MultiplicityEncoding::Unary
+            ),
+            Err(Error::InvalidParameter(
+                "a unary block has a set bit after an unset one"
+            ))
+        );
+        assert_eq!(
+            Multiset::try_from_bitset_with_encoding(
+                &[false, true, true],
+                3,
+                MultiplicityEncoding::OneHot
+            ),
+            Err(Error::InvalidParameter(
+                "a one-hot block has more than one set bit"
+            ))
+        );
+        assert_eq!(
+            multiset.try_to_bitset_with_encoding(usize::MAX, 4, MultiplicityEncoding::Unary),
+            Err(Error::InvalidParameter("the bitset length overflows"))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_to_bitset_count_too_large() {
+        Multiset::new(&[1], &[3]).to_bitset(5, 2);
+    }
 }
@@ -0,0 +1,642 @@
+//! Generators for sets with exactly prescribed overlaps. Subsets of sets are given as lists of
+//! set indices, in any order.
+use crate::error::Error;
+use crate::sets::Set;
+use rand::rngs::OsRng;
+use rand::seq::index::sample;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+/// The largest subset of sets that `try_gen_sets_with_intersection_sizes` accepts, as it visits
+/// every subset of a given subset.
+pub const MAX_INTERSECTION_SUBSET_SIZE: usize = 20;
+
+/// Generates `set_count` random sets with the given Venn regions. Panics if this is infeasible,
+/// see `try_gen_sets_with_venn_regions`.
+pub fn gen_sets_with_venn_regions(
+    set_count: usize,
+    universe: usize,
+    regions: &HashMap<Vec<usize>, usize>,
+) -> Vec<Set> {
+    try_gen_sets_with_venn_regions(set_count, universe, regions)
+        .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Generates `set_count` random sets where, for every subset of sets in `regions`, exactly that
+/// many elements are contained in those sets and in no others. Subsets that are not given are
+/// empty regions.
+pub fn try_gen_sets_with_venn_regions(
+    set_count: usize,
+    universe: usize,
+    regions: &HashMap<Vec<usize>, usize>,
+) -> Result<Vec<Set>, Error> {
+    try_gen_sets_with_venn_regions_with_rng(&mut OsRng, set_count, universe, regions)
+}
+
+/// Same as `try_gen_sets_with_venn_regions`, but draws from `rng`, e.g. a seeded one for
+/// reproducible sets.
+pub fn try_gen_sets_with_venn_regions_with_rng<R: Rng + ?Sized>(
+    rng: &mut R,
+    set_count: usize,
+    universe: usize,
+    regions: &HashMap<Vec<usize>, usize>,
+) -> Result<Vec<Set>, Error> {
+    let mut normalized = Vec::with_capacity(regions.len());
+    let mut subsets = HashSet::new();
+    for (subset, size) in regions {
+        let subset = normalize(subset, set_count)?;
+        if !subsets.insert(subset.clone()) {
+            return Err(Error::InvalidParameter(
+                "a subset of sets is given more than once",
+            ));
+        }
+        normalized.push((subset, *size));
+    }
+    // The iteration order of the map differs between runs, which would break seeded generation
+    normalized.sort_unstable();
+
+    let required: usize = normalized.iter().map(|(_, size)| size).sum();
+    if required > universe {
+        return Err(Error::Infeasible(
+            "the regions hold more elements than the universe",
+        ));
+    }
+
+    let mut elements = sample(rng, universe, required).into_iter();
+    let mut sets = vec![Set::new(&[]); set_count];
+    for (subset, size) in &normalized {
+        for element in elements.by_ref().take(*size) {
+            for index in subset {
+                sets[*index].elements.insert(element);
+            }
+        }
+    }
+
+    Ok(sets)
+}
+
+/// Generates `set_count` random sets with the given intersection sizes. Panics if this is
+/// infeasible, see `try_gen_sets_with_intersection_sizes`.
+pub fn gen_sets_with_intersection_sizes(
+    set_count: usize,
+    universe: usize,
+    intersection_sizes: &HashMap<Vec<usize>, usize>,
+) -> Vec<Set> {
+    try_gen_sets_with_intersection_sizes(set_count, universe, intersection_sizes)
+        .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Generates `set_count` random sets where, for every subset of sets in `intersection_sizes`,
+/// the intersection of those sets has exactly that size. A single-set subset gives the size of
+/// that set. Intersections that are not given are empty. A subset may contain at most
+/// `MAX_INTERSECTION_SUBSET_SIZE` sets.
+pub fn try_gen_sets_with_intersection_sizes(
+    set_count: usize,
+    universe: usize,
+    intersection_sizes: &HashMap<Vec<usize>, usize>,
+) -> Result<Vec<Set>, Error> {
+    try_gen_sets_with_intersection_sizes_with_rng(
+        &mut OsRng,
+        set_count,
+        universe,
+        intersection_sizes,
+    )
+}
+
+/// Same as `try_gen_sets_with_intersection_sizes`, but draws from `rng`, e.g. a seeded one for
+/// reproducible sets.
+pub fn try_gen_sets_with_intersection_sizes_with_rng<R: Rng + ?Sized>(
+    rng: &mut R,
+    set_count: usize,
+    universe: usize,
+    intersection_sizes: &HashMap<Vec<usize>, usize>,
+) -> Result<Vec<Set>, Error> {
+    if set_count > 64 {
+        return Err(Error::InvalidParameter(
+            "intersection sizes are supported for at most 64 sets",
+        ));
+    }
+
+    // Moebius inversion: the region of exactly the sets in S is the alternating sum of the
+    // intersection sizes of all supersets of S
+    let mut region_sizes: HashMap<u64, i128> = HashMap::new();
+    let mut masks = HashSet::new();
+    for (subset, size) in intersection_sizes {
+        let subset = normalize(subset, set_count)?;
+        if subset.len() > MAX_INTERSECTION_SUBSET_SIZE {
+            return Err(Error::InvalidParameter(
+                "a subset of sets is larger than MAX_INTERSECTION_SUBSET_SIZE",
+            ));
+        }
+
+        let mask = subset.iter().fold(0u64, |mask, index| mask | 1 << index);
+        if !masks.insert(mask) {
+            return Err(Error::InvalidParameter(
+                "a subset of sets is given more than once",
+            ));
+        }
+
+        let mut submask = mask;
+        while submask != 0 {
+            let sign = if (mask ^ submask).count_ones() % 2 == 0 {
+                1
+            } else {
+                -1
+            };
+            *region_sizes.entry(submask).or_insert(0) += sign * *size as i128;
+
+            submask = (submask - 1) & mask;
+        }
+    }
+
+    let mut regions = HashMap::new();
+    for (mask, size) in region_sizes {
+        if size < 0 {
+            return Err(Error::Infeasible(
+                "the intersection sizes leave a negative number of elements for a Venn region",
+            ));
+        }
+        if size > 0 {
+            regions.insert(subset_of_mask(mask, set_count), size as usize);
+        }
+    }
+
+    try_gen_sets_with_venn_regions_with_rng(rng, set_count, universe, &regions)
+}
+
+/// Generates random sets with the given pairwise overlaps. Panics if this is infeasible, see
+/// `try_gen_sets_with_overlap_matrix`.
+pub fn gen_sets_with_overlap_matrix(matrix: &[Vec<usize>], universe: usize) -> Vec<Set> {
+    try_gen_sets_with_overlap_matrix(matrix, universe).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Generates random sets where set `i` has size `matrix[i][i]` and sets `i` and `j` share
+/// exactly `matrix[i][j]` elements. Elements are shared by three or more sets only where the
+/// overlaps of a set add up to more than its size, so an error is only returned if no sets in
+/// the universe meet the matrix.
+///
+/// Such elements are found by a backtracking search, which is immediate if no set needs them
+/// but can take exponential time in the number of sets otherwise.
+pub fn try_gen_sets_with_overlap_matrix(
+    matrix: &[Vec<usize>],
+    universe: usize,
+) -> Result<Vec<Set>, Error> {
+    try_gen_sets_with_overlap_matrix_with_rng(&mut OsRng, matrix, universe)
+}
+
+/// Same as `try_gen_sets_with_overlap_matrix`, but draws from `rng`, e.g. a seeded one for
+/// reproducible sets.
+pub fn try_gen_sets_with_overlap_matrix_with_rng<R: Rng + ?Sized>(
+    rng: &mut R,
+    matrix: &[Vec<usize>],
+    universe: usize,
+) -> Result<Vec<Set>, Error> {
+    let set_count = matrix.len();
+    if matrix.iter().any(|row| row.len() != set_count) {
+        return Err(Error::InvalidParameter("the overlap matrix is not square"));
+    }
+    if set_count > 64 {
+        return Err(Error::InvalidParameter(
+            "overlap matrices are supported for at most 64 sets",
+        ));
+    }
+    for (i, row) in matrix.iter().enumerate() {
+        if row
+            .iter()
+            .enumerate()
+            .any(|(j, size)| *size != matrix[j][i])
+        {
+            return Err(Error::InvalidParameter(
+                "the overlap matrix is not symmetric",
+            ));
+        }
+    }
+
+    // The elements needed without regions of three or more sets, each of which adds
+    // `(m - 1) * (m - 2) / 2` elements by inclusion-exclusion
+    let pairwise_element_count = (0..set_count)
+        .map(|i| {
+            matrix[i][i] as i128
+                - matrix[i][i + 1..]
+                    .iter()
+                    .map(|size| *size as i128)
+                    .sum::<i128>()
+        })
+        .sum::<i128>();
+    let new_search = || OverlapSearch {
+        residual: matrix.to_vec(),
+        regions: Vec::new(),
+        failed: HashMap::new(),
+    };
+
+    // Most matrices are met by the first regions that the search tries, so it is only repeated
+    // with a limit on the elements if these do not fit in the universe
+    let mut search = new_search();
+    if !search.solve(usize::MAX, None) {
+        return Err(Error::Infeasible(
+            "no sets have exactly these pairwise overlaps",
+        ));
+    }
+    let budget = universe as i128 - pairwise_element_count;
+    if search.element_count() as i128 > budget {
+        search = new_search();
+        if budget < 0 || !search.solve(budget.min(usize::MAX as i128) as usize, None) {
+            return Err(Error::Infeasible(
+                "the regions hold more elements than the universe",
+            ));
+        }
+    }
+
+    // What the regions of three or more sets leave are the regions of one and two sets
+    let mut regions = HashMap::new();
+    for (mask, size) in &search.regions {
+        *regions.entry(subset_of_mask(*mask, set_count)).or_insert(0) += size;
+    }
+    for (i, row) in search.residual.iter().enumerate() {
+        let overlaps: usize = row
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, size)| size)
+            .sum();
+        if row[i] > overlaps {
+            regions.insert(vec![i], row[i] - overlaps);
+        }
+        for (j, size) in row.iter().enumerate().skip(i + 1) {
+            if *size > 0 {
+                regions.insert(vec![i, j], *size);
+            }
+        }
+    }
+
+    try_gen_sets_with_venn_regions_with_rng(rng, set_count, universe, &regions)
+}
+
+/// The number of failed states that `OverlapSearch` remembers, which bounds its memory.
+const MAX_FAILED_STATES: usize = 1 << 16;
+
+/// Searches the regions of three or more sets that meet a matrix of pairwise overlaps, after
+/// which the regions of one and two sets follow. Without such regions, a set holds each of its
+/// overlaps and its own elements, so a set whose overlaps add up to more than its size has an
+/// excess. An element in `m >= 3` sets counts towards `m - 1` overlaps of each of them, so it
+/// makes up for `m - 2` of their excess.
+///
+/// The regions are chosen in a canonical order: each one contains the first set with an excess
+/// left, is no larger than needed to make up for it, and the regions chosen for that set are in
+/// decreasing order of their masks. Every solution contains regions that are chosen this way
+/// and use no more elements, so the search only fails if there is no solution.
+struct OverlapSearch {
+    /// The sizes and pairwise overlaps that the chosen regions leave.
+    residual: Vec<Vec<usize>>,
+    /// The chosen regions as masks of sets with their sizes.
+    regions: Vec<(u64, usize)>,
+    /// The largest budget with which a residual and mask bound have failed.
+    failed: HashMap<(Vec<Vec<usize>>, Option<u64>), usize>,
+}
+
+impl OverlapSearch {
+    fn excess(&self, i: usize) -> usize {
+        (0..self.residual.len())
+            .filter(|j| *j != i)
+            .fold(0usize, |sum, j| sum.saturating_add(self.residual[i][j]))
+            .saturating_sub(self.residual[i][i])
+    }
+
+    /// Whether the residual can still be met, as checked by necessary conditions.
+    fn is_feasible(&self, budget: usize) -> bool {
+        let r = &self.residual;
+        let set_count = r.len();
+
+        // Two sets share at most the elements of either, and the elements that set `i` shares
+        // with `j` or `k` are at most its size: `r[i][j] + r[i][k] - r[i][j][k] <= r[i][i]`
+        for i in 0..set_count {
+            for j in 0..set_count {
+                if r[i][j] > r[i][i] {
+                    return false;
+                }
+                for k in j + 1..set_count {
+                    if i != j
+                        && i != k
+                        && r[i][j] as u128 + r[i][k] as u128 > r[i][i] as u128 + r[j][k] as u128
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        // A region makes up for at most `set_count - 2` excess per `set_count - 1` overlaps of
+        // a set, and adds at least a third of an element per excess
+        let mut excess_sum = 0u128;
+        for (i, row) in r.iter().enumerate() {
+            let excess = self.excess(i) as u128;
+            let overlaps = row[i] as u128 + excess;
+            if excess * (set_count as u128 - 1) > overlaps * (set_count as u128).saturating_sub(2) {
+                return false;
+            }
+            excess_sum += excess;
+        }
+
+        excess_sum <= budget as u128 * 3
+    }
+
+    /// Chooses regions that make up for all excess and add at most `budget` elements. If the
+    /// previous region was chosen for the same set, the next one has a mask below `bound`.
+    fn solve(&mut self, budget: usize, bound: Option<u64>) -> bool {
+        let set_count = self.residual.len();
+        if !self.is_feasible(budget) {
+            return false;
+        }
+        let i = match (0..set_count).find(|i| self.excess(*i) > 0) {
+            Some(i) => i,
+            None => return true,
+        };
+
+        let key = (self.residual.clone(), bound);
+        if self
+            .failed
+            .get(&key)
+            .is_some_and(|failed| *failed >= budget)
+        {
+            return false;
+        }
+
+        let others: Vec<usize> = (0..set_count)
+            .filter(|j| *j != i && self.residual[i][*j] > 0)
+            .collect();
+        for choice in (0..1u64 << others.len()).rev() {
+            if choice.count_ones() < 2 {
+                continue;
+            }
+            let members: Vec<usize> = std::iter::once(i)
+                .chain(
+                    others
+                        .iter()
+                        .enumerate()
+                        .filter(|(bit, _)| choice >> bit & 1 == 1)
+                        .map(|(_, j)| *j),
+                )
+                .collect();
+            let mask = members.iter().fold(0u64, |mask, j| mask | 1 << j);
+            if bound.is_some_and(|bound| mask >= bound) {
+                continue;
+            }
+
+            let member_count = members.len();
+            let element_cost = (member_count - 1) * (member_count - 2) / 2;
+            let residual = &self.residual;
+            let max_size = members
+                .iter()
+                .flat_map(|a| members.iter().map(move |b| residual[*a][*b]))
+                .min()
+                .unwrap_or(0)
+                .min(self.excess(i).div_ceil(member_count - 2))
+                .min(budget / element_cost);
+
+            for size in (1..=max_size).rev() {
+                self.add_region(&members, size, false);
+                let next_bound = Some(mask).filter(|_| self.excess(i) > 0);
+                if self.solve(budget - size * element_cost, next_bound) {
+                    self.regions.push((mask, size));
+                    return true;
+                }
+                self.add_region(&members, size, true);
+            }
+        }
+
+        if self.failed.len() < MAX_FAILED_STATES {
+            self.failed.insert(key, budget);
+        }
+        false
+    }
+
+    /// The elements that the chosen regions add to those of the pairwise overlaps.
+    fn element_count(&self) -> usize {
+        self.regions
+            .iter()
+            .map(|(mask, size)| {
+                let member_count = mask.count_ones() as usize;
+                size * (member_count - 1) * (member_count - 2) / 2
+            })
+            .sum()
+    }
+
+    /// Takes a region from the residual, or gives it back if `undo` is set.
+    fn add_region(&mut self, members: &[usize], size: usize, undo: bool) {
+        for a in members {
+            for b in members {
+                if undo {
+                    self.residual[*a][*b] += size;
+                } else {
+                    self.residual[*a][*b] -= size;
+                }
+            }
+        }
+    }
+}
+
+fn subset_of_mask(mask: u64, set_count: usize) -> Vec<usize> {
+    (0..set_count).filter(|i| mask & (1 << i) != 0).collect()
+}
+
+/// Validates the subset and returns its set indices sorted and without duplicates.
+fn normalize(subset: &[usize], set_count: usize) -> Result<Vec<usize>, Error> {
+    if subset.is_empty() {
+        return Err(Error::InvalidParameter("a subset of sets is empty"));
+    }
+
+    if subset.iter().any(|index| *index >= set_count) {
+        return Err(Error::InvalidParameter(
+            "a subset refers to a set index that is not below the set count",
+        ));
+    }
+
+    let mut subset = subset.to_vec();
+    subset.sort_unstable();
+    subset.dedup();
+    Ok(subset)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::overlaps::{
+        gen_sets_with_overlap_matrix, try_gen_sets_with_intersection_sizes,
+        try_gen_sets_with_overlap_matrix, try_gen_sets_with_overlap_matrix_with_rng,
+        try_gen_sets_with_venn_regions,
+    };
+    use crate::sets::Set;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    fn assert_overlaps(sets: &[Set], matrix: &[Vec<usize>]) {
+        assert_eq!(sets.len(), matrix.len());
+        for i in 0..sets.len() {
+            for j in 0..sets.len() {
+                assert_eq!(sets[i].intersect(&sets[j]).len(), matrix[i][j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_venn_regions() {
+        let regions: HashMap<Vec<usize>, usize> = vec![
+            (vec![0], 3),
+            (vec![1, 0], 2),
+            (vec![0, 1, 2], 4),
+            (vec![2], 1),
+        ]
+        .into_iter()
+        .collect();
+        let sets = try_gen_sets_with_venn_regions(3, 100, &regions).unwrap();
+
+        assert_eq!(sets[0].len(), 9);
+        assert_eq!(sets[1].len(), 6);
+        assert_eq!(sets[2].len(), 5);
+        assert_eq!(sets[0].intersect(&sets[1]).len(), 6);
+        assert_eq!(Set::intersection(&sets).len(), 4);
+        assert_eq!(Set::union(&sets).len(), 10);
+    }
+
+    #[test]
+    fn test_intersection_sizes() {
+        let intersection_sizes: HashMap<Vec<usize>, usize> = vec![
+            (vec![0], 10),
+            (vec![1], 8),
+            (vec![2], 6),
+            (vec![0, 1], 5),
+            (vec![0, 2], 4),
+            (vec![1, 2], 3),
+            (vec![0, 1, 2], 2),
+        ]
+        .into_iter()
+        .collect();
+        let sets = try_gen_sets_with_intersection_sizes(3, 100, &intersection_sizes).unwrap();
+
+        for (subset, size) in &intersection_sizes {
+            let subset_sets: Vec<Set> = subset.iter().map(|i| sets[*i].clone()).collect();
+            let intersection = subset_sets[1..]
+                .iter()
+                .fold(subset_sets[0].clone(), |result, set| result.intersect(set));
+            assert_eq!(intersection.len(), *size);
+        }
+    }
+
+    #[test]
+    fn test_overlap_matrix() {
+        let matrix = vec![vec![10, 3, 0], vec![3, 5, 2], vec![0, 2, 7]];
+        assert_overlaps(&gen_sets_with_overlap_matrix(&matrix, 50), &matrix);
+    }
+
+    #[test]
+    fn test_overlap_matrix_shared_by_three_sets() {
+        // Only met with elements that are in all three sets
+        let matrix = vec![vec![2, 2, 2], vec![2, 2, 2], vec![2, 2, 2]];
+        assert_overlaps(&gen_sets_with_overlap_matrix(&matrix, 2), &matrix);
+
+        // Met by {1, 2, 3}, {1, 2, 4} and {1, 2, 5}, or with fewer elements by {1, 2, 3},
+        // {1, 2, 4} and {1, 3, 4}
+        let matrix = vec![vec![3, 2, 2], vec![2, 3, 2], vec![2, 2, 3]];
+        assert_overlaps(&gen_sets_with_overlap_matrix(&matrix, 5), &matrix);
+        assert_overlaps(&gen_sets_with_overlap_matrix(&matrix, 4), &matrix);
+        assert_eq!(
+            try_gen_sets_with_overlap_matrix(&matrix, 3),
+            Err(Error::Infeasible(
+                "the regions hold more elements than the universe"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_overlap_matrix_fewest_elements() {
+        // One element in all sets and one in each set alone needs 4 elements, one element per
+        // pair of sets only 3
+        let matrix = vec![vec![2, 1, 1], vec![1, 2, 1], vec![1, 1, 2]];
+        assert_overlaps(&gen_sets_with_overlap_matrix(&matrix, 3), &matrix);
+    }
+
+    #[test]
+    fn test_overlap_matrix_with_rng() {
+        let matrix = vec![
+            vec![20, 8, 5, 3],
+            vec![8, 15, 4, 2],
+            vec![5, 4, 10, 1],
+            vec![3, 2, 1, 6],
+        ];
+        let sets =
+            try_gen_sets_with_overlap_matrix_with_rng(&mut StdRng::seed_from_u64(1), &matrix, 100)
+                .unwrap();
+
+        assert_overlaps(&sets, &matrix);
+        assert_eq!(
+            try_gen_sets_with_overlap_matrix_with_rng(&mut StdRng::seed_from_u64(1), &matrix, 100)
+                .unwrap(),
+            sets
+        );
+    }
+
+    #[test]
+    fn test_infeasible() {
+        // The sets share more elements than the second set has
+        let matrix = vec![vec![10, 3, 0], vec![3, 4, 2], vec![0, 2, 7]];
+        assert_eq!(
+            try_gen_sets_with_overlap_matrix(&matrix, 50),
+            Err(Error::Infeasible(
+                "no sets have exactly these pairwise overlaps"
+            ))
+        );
+        // An overlap larger than a set
+        assert_eq!(
+            try_gen_sets_with_overlap_matrix(&[vec![1, 2], vec![2, 3]], 50),
+            Err(Error::Infeasible(
+                "no sets have exactly these pairwise overlaps"
+            ))
+        );
+
+        let matrix = vec![vec![10, 3], vec![3, 5]];
+        assert_eq!(
+            try_gen_sets_with_overlap_matrix(&matrix, 11),
+            Err(Error::Infeasible(
+                "the regions hold more elements than the universe"
+            ))
+        );
+
+        assert_eq!(
+            try_gen_sets_with_overlap_matrix(&[vec![1, 2], vec![3, 1]], 50),
+            Err(Error::InvalidParameter(
+                "the overlap matrix is not symmetric"
+            ))
+        );
+        assert_eq!(
+            try_gen_sets_with_overlap_matrix(&[vec![1, 0, 0], vec![0, 1, 0], vec![0]], 50),
+            Err(Error::InvalidParameter("the overlap matrix is not square"))
+        );
+
+        let intersection_sizes: HashMap<Vec<usize>, usize> =
+            vec![(vec![0, 1], 1), (vec![1, 0], 1)].into_iter().collect();
+        assert_eq!(
+            try_gen_sets_with_intersection_sizes(2, 50, &intersection_sizes),
+            Err(Error::InvalidParameter(
+                "a subset of sets is given more than once"
+            ))
+        );
+
+        let regions: HashMap<Vec<usize>, usize> = vec![(vec![0, 3], 1)].into_iter().collect();
+        assert_eq!(
+            try_gen_sets_with_venn_regions(3, 50, &regions),
+            Err(Error::InvalidParameter(
+                "a subset refers to a set index that is not below the set count"
+            ))
+        );
+
+        let intersection_sizes: HashMap<Vec<usize>, usize> =
+            vec![((0..40).collect(), 1)].into_iter().collect();
+        assert_eq!(
+            try_gen_sets_with_intersection_sizes(40, 50, &intersection_sizes),
+            Err(Error::InvalidParameter(
+                "a subset of sets is larger than MAX_INTERSECTION_SUBSET_SIZE"
+            ))
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! Packing of Bloom filters and bitsets into plaintext slots for homomorphic encryption, and the
+//! plaintext reference of aggregating them.
+//!
+//! Each slot holds `slot_bit_width` bits, split into fields of `field_bit_width` bits, and each
+//! field holds one bin. The fields are wide enough to hold the sum of one bin over all parties,
+//! so that adding packed plaintexts (or ciphertexts under an additively homomorphic scheme) sums
+//! the filters bin-wise without carrying into the next field. Bin `i` is stored in plaintext
+//! `i / (slot_count * fields_per_slot)`, in field `i % fields_per_slot` of its slot, starting at
+//! the least-significant bit.
+use crate::error::Error;
+use std::cmp;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PackingParameters {
+    /// The number of slots per plaintext, e.g. 1 for Paillier or ElGamal and the ring
+    /// dimension for batched lattice schemes.
+    pub slot_count: usize,
+    pub slot_bit_width: u32,
+    pub field_bit_width: u32,
+}
+
+impl PackingParameters {
+    /// Chooses the narrowest field that holds the sum of one bin over `party_count` parties.
+    /// Panics if `slot_count` is zero, `slot_bit_width` is not between 1 and 64, or a field does
+    /// not fit in a slot.
+    pub fn new(slot_count: usize, slot_bit_width: u32, party_count: usize) -> Self {
+        let field_bit_width = cmp::max(1, usize::BITS - party_count.leading_zeros());
+        PackingParameters::with_field_bit_width(slot_count, slot_bit_width, field_bit_width)
+    }
+
+    /// Same as `new`, but with an explicit field width.
+    pub fn with_field_bit_width(
+        slot_count: usize,
+        slot_bit_width: u32,
+        field_bit_width: u32,
+    ) -> Self {
+        assert!(slot_count > 0);
+        assert!(slot_bit_width > 0 && slot_bit_width <= 64);
+        assert!(field_bit_width > 0 && field_bit_width <= slot_bit_width);
+
+        PackingParameters {
+            slot_count,
+            slot_bit_width,
+            field_bit_width,
+        }
+    }
+
+    pub fn fields_per_slot(&self) -> usize {
+        (self.slot_bit_width / self.field_bit_width) as usize
+    }
+
+    pub fn bins_per_plaintext(&self) -> usize {
+        self.slot_count * self.fields_per_slot()
+    }
+
+    /// The number of plaintexts needed for `bin_count` bins.
+    pub fn plaintext_count(&self, bin_count: usize) -> usize {
+        bin_count.div_ceil(self.bins_per_plaintext())
+    }
+
+    /// The largest value a field can hold.
+    pub fn max_field_value(&self) -> u64 {
+        u64::MAX >> (64 - self.field_bit_width)
+    }
+
+    /// Packs the bins of a Bloom filter or bitset into plaintexts of `slot_count` slots. The last
+    /// plaintext is padded with empty fields.
+    pub fn pack(&self, bins: &[bool]) -> Vec<Vec<u64>> {
+        let counts: Vec<u64> = bins.iter().map(|bin| *bin as u64).collect();
+        self.pack_counts(&counts)
+    }
+
+    /// Same as `pack`, but for bins that hold counts. Panics if a count does not fit in a field.
+    pub fn pack_counts(&self, counts: &[u64]) -> Vec<Vec<u64>> {
+        let fields_per_slot = self.fields_per_slot();
+
+        let mut plaintexts = vec![vec![0; self.slot_count]; self.plaintext_count(counts.len())];
+        for (i, count) in counts.iter().enumerate() {
+            assert!(*count <= self.max_field_value());
+
+            let slot = i / fields_per_slot;
+            let field = (i % fields_per_slot) as u32;
+            plaintexts[slot / self.slot_count][slot % self.slot_count] |=
+                count << (field * self.field_bit_width);
+        }
+
+        plaintexts
+    }
+
+    /// Recovers the first `bin_count` counts from packed plaintexts. Panics if there are too few
+    /// plaintexts or a plaintext has the wrong slot count.
+    pub fn unpack(&self, plaintexts: &[Vec<u64>], bin_count: usize) -> Vec<u64> {
+        assert!(plaintexts.len() >= self.plaintext_count(bin_count));
+        let fields_per_slot = self.fields_per_slot();
+
+        (0..bin_count)
+            .map(|i| {
+                let slot = i / fields_per_slot;
+                let field = (i % fields_per_slot) as u32;
+                let plaintext = &plaintexts[slot / self.slot_count];
+                assert_eq!(plaintext.len(), self.slot_count);
+
+                (plaintext[slot % self.slot_count] >> (field * self.field_bit_width))
+                    & self.max_field_value()
+            })
+            .collect()
+    }
+
+    /// Adds packed plaintexts slot-wise, the plaintext counterpart of adding the ciphertexts.
+    /// Panics if `packed` is empty, there are more parties than a field can count, the parties
+    /// differ in their number of plaintexts or a plaintext does not have `slot_count` slots.
+    pub fn add_packed(&self, packed: &[Vec<Vec<u64>>]) -> Vec<Vec<u64>> {
+        self.try_add_packed(packed)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_add_packed(&self, packed: &[Vec<Vec<u64>>]) -> Result<Vec<Vec<u64>>, Error> {
+        let (first, rest) = packed.split_first().ok_or(Error::NotEnoughSets {
+            required: 1,
+            given: 0,
+        })?;
+
+        if packed.iter().any(|plaintexts| {
+            plaintexts.len() != first.len()
+                || plaintexts
+                    .iter()
+                    .any(|plaintext| plaintext.len() != self.slot_count)
+        }) {
+            return Err(Error::InvalidParameter(
+                "the packed filters differ in shape",
+            ));
+        }
+        // A field that overflows would carry into the next one
+        if packed.len() as u64 > self.max_field_value() {
+            return Err(Error::InvalidParameter(
+                "there are more parties than a field can count",
+            ));
+        }
+
+        let mut sum = first.clone();
+        for plaintexts in rest {
+            for (sum_plaintext, plaintext) in sum.iter_mut().zip(plaintexts) {
+                for (sum_slot, slot) in sum_plaintext.iter_mut().zip(plaintext) {
+                    *sum_slot = sum_slot.wrapping_add(*slot);
+                }
+            }
+        }
+
+        Ok(sum)
+    }
+}
+
+/// Sums the bins of several parties' Bloom filters or bitsets into a count per bin. Panics if
+/// `filters` is empty or the filters differ in length.
+pub fn aggregate_bloom_filters(filters: &[Vec<bool>]) -> Vec<usize> {
+    try_aggregate_bloom_filters(filters).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn try_aggregate_bloom_filters(filters: &[Vec<bool>]) -> Result<Vec<usize>, Error> {
+    let (first, rest) = filters.split_first().ok_or(Error::NotEnoughSets {
+        required: 1,
+        given: 0,
+    })?;
+
+    let mut counts: Vec<usize> = first.iter().map(|bin| *bin as usize).collect();
+    for filter in rest {
+        if filter.len() != counts.len() {
+            return Err(Error::InvalidParameter("the filters differ in length"));
+        }
+
+        for (count, bin) in counts.iter_mut().zip(filter) {
+            *count += *bin as usize;
+        }
+    }
+
+    Ok(counts)
+}
+
+/// The bins that are set in at least `threshold` filters, i.e. the Bloom filter of the threshold
+/// intersection (with false positives) when applied to aggregated Bloom filters.
+pub fn threshold_bins(counts: &[usize], threshold: usize) -> Vec<bool> {
+    counts.iter().map(|count| *count >= threshold).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{bloom_filter_contains, Xxh3Hasher};
+    use crate::error::Error;
+    use crate::packing::{
+        aggregate_bloom_filters, threshold_bins, try_aggregate_bloom_filters, PackingParameters,
+    };
+    use crate::sets::{gen_sets_with_threshold_intersection, Set};
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_parameters() {
+        let parameters = PackingParameters::new(4, 64, 5);
+        assert_eq!(parameters.field_bit_width, 3);
+        assert_eq!(parameters.fields_per_slot(), 21);
+        assert_eq!(parameters.bins_per_plaintext(), 84);
+        assert_eq!(parameters.plaintext_count(85), 2);
+        assert_eq!(parameters.max_field_value(), 7);
+
+        assert_eq!(PackingParameters::new(1, 64, 1).field_bit_width, 1);
+        assert_eq!(PackingParameters::new(1, 64, 8).field_bit_width, 4);
+    }
+
+    #[test]
+    fn test_pack_layout() {
+        let parameters = PackingParameters::with_field_bit_width(2, 8, 2);
+        let packed = parameters.pack(&[true, false, true, true, false, true, false, false, true]);
+
+        assert_eq!(
+            packed,
+            vec![vec![0b01_01_00_01, 0b00_00_01_00], vec![0b00_00_00_01, 0]]
+        );
+    }
+
+    #[test]
+    fn test_pack_round_trip() {
+        let parameters = PackingParameters::new(3, 20, 1);
+        let bins = Set::new(&[0, 5, 17, 63, 100]).to_bitset(101);
+
+        let packed = parameters.pack(&bins);
+        assert_eq!(packed.len(), parameters.plaintext_count(101));
+        let unpacked = parameters.unpack(&packed, 101);
+        assert_eq!(
+            unpacked,
+            bins.iter().map(|bin| *bin as u64).collect::<Vec<u64>>()
+        );
+    }
+
+    #[test]
+    fn test_add_packed_matches_aggregation() {
+        let sets = gen_sets_with_threshold_intersection(5, 20, 200, 3, 6);
+        let filters: Vec<Vec<bool>> = sets
+            .iter()
+            .map(|set| set.to_bloom_filter::<H>(500, 3))
+            .collect();
+
+        let parameters = PackingParameters::new(8, 32, sets.len());
+        let packed: Vec<Vec<Vec<u64>>> = filters.iter().map(|f| parameters.pack(f)).collect();
+        let sum = parameters.add_packed(&packed);
+
+        let counts = aggregate_bloom_filters(&filters);
+        assert_eq!(
+            parameters.unpack(&sum, 500),
+            counts
+                .iter()
+                .map(|count| *count as u64)
+                .collect::<Vec<u64>>()
+        );
+
+        // The threshold intersection is contained in the thresholded filter
+        let bins = threshold_bins(&counts, 3);
+        for element in &Set::threshold_intersection(&sets, 3).elements {
+            assert!(bloom_filter_contains::<H>(&bins, element, 3));
+        }
+    }
+
+    #[test]
+    fn test_aggregate_invalid() {
+        assert_eq!(
+            try_aggregate_bloom_filters(&[]),
+            Err(Error::NotEnoughSets {
+                required: 1,
+                given: 0
+            })
+        );
+        assert!(matches!(
+            try_aggregate_bloom_filters(&[vec![true], vec![true, false]]),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_packed_invalid() {
+        let parameters = PackingParameters::new(2, 8, 2);
+        let error = Err(Error::InvalidParameter(
+            "the packed filters differ in shape",
+        ));
+
+        // Every party is checked, including the first and a single one
+        assert_eq!(parameters.try_add_packed(&[vec![vec![1, 2, 3]]]), error);
+        assert_eq!(
+            parameters.try_add_packed(&[vec![vec![1]], vec![vec![1, 2]]]),
+            error
+        );
+        assert_eq!(
+            parameters.try_add_packed(&[vec![vec![1, 2]], vec![vec![1, 2], vec![3, 4]]]),
+            error
+        );
+        assert_eq!(
+            parameters.try_add_packed(&[vec![vec![1, 2]], vec![vec![3, 4]]]),
+            Ok(vec![vec![4, 6]])
+        );
+
+        // Two-bit fields count at most three parties
+        let parameters = PackingParameters::new(1, 8, 3);
+        let packed = vec![parameters.pack(&[true]); 4];
+        assert_eq!(parameters.try_add_packed(&packed[..3]), Ok(vec![vec![3]]));
+        assert_eq!(
+            parameters.try_add_packed(&packed),
+            Err(Error::InvalidParameter(
+                "there are more parties than a field can count"
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_count_too_large() {
+        PackingParameters::new(1, 64, 3).pack_counts(&[4]);
+    }
+}
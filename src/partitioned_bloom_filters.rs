@@ -0,0 +1,309 @@
+//! Partitioned Bloom filters, where the bins are split into `hash_count` equal slices and hash
+//! `i` only indexes slice `i`. Every element therefore sets exactly `hash_count` distinct bins,
+//! one per slice.
+use crate::bloom_filters::{seeds, ElementHasher, MultisetEncoding};
+use crate::multisets::Multiset;
+use crate::sets::Set;
+
+/// For a maximum error rate and maximum set size, returns the lowest bin count and the
+/// corresponding hash count that assure this maximum error rate. The bin count is a multiple of
+/// the hash count.
+pub fn gen_partitioned_bloom_filter_params(
+    max_error_rate: f64,
+    max_set_size: usize,
+) -> (usize, usize) {
+    let mut h = 1;
+    let mut previous: Option<usize> = None;
+    loop {
+        // A slice of size s has error rate 1 - (1 - 1/s)^n, solved for s
+        let slice_error_rate = max_error_rate.powf(1. / h as f64);
+        let slice_size =
+            (-1. / ((-slice_error_rate).ln_1p() / max_set_size as f64).exp_m1()).ceil() as usize;
+        let current = h * slice_size;
+
+        if let Some(p) = previous {
+            if p < current {
+                return (p, h - 1);
+            }
+        }
+
+        h += 1;
+        previous = Some(current);
+    }
+}
+
+/// Panics if `hash_count` is 0 or `bin_count` is not a multiple of `hash_count`.
+pub fn partitioned_bloom_filter_indices<H: ElementHasher>(
+    element: &usize,
+    bin_count: usize,
+    hash_count: usize,
+) -> impl Iterator<Item = usize> + '_ {
+    slice_indices(
+        H::hash_element_multiple_seeds(element, &seeds(hash_count)),
+        bin_count,
+    )
+}
+
+/// The indices of copy `index` of `element`. Panics under the same conditions as
+/// `partitioned_bloom_filter_indices`.
+pub fn partitioned_bloom_filter_copy_indices<H: ElementHasher>(
+    element: &usize,
+    index: usize,
+    max_multiplicity: usize,
+    bin_count: usize,
+    hash_count: usize,
+    encoding: MultisetEncoding,
+) -> impl Iterator<Item = usize> {
+    slice_indices(
+        encoding.hash_copy::<H>(element, index, max_multiplicity, &seeds(hash_count)),
+        bin_count,
+    )
+}
+
+/// Maps hash `i` into slice `i`. Panics if there are no hashes or `bin_count` is not a multiple
+/// of the hash count.
+fn slice_indices(hashes: Vec<usize>, bin_count: usize) -> impl Iterator<Item = usize> {
+    assert!(!hashes.is_empty(), "the hash count must be at least 1");
+    assert!(bin_count % hashes.len() == 0);
+    let slice_size = bin_count / hashes.len();
+
+    hashes
+        .into_iter()
+        .enumerate()
+        .map(move |(slice, hash)| slice * slice_size + hash % slice_size)
+}
+
+pub fn partitioned_bloom_filter_contains<H: ElementHasher>(
+    bins: &[bool],
+    element: &usize,
+    hash_count: usize,
+) -> bool {
+    partitioned_bloom_filter_indices::<H>(element, bins.len(), hash_count).all(|index| bins[index])
+}
+
+impl Set {
+    pub fn to_partitioned_bloom_filter<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+    ) -> Vec<bool> {
+        let mut bins = vec![false; bin_count];
+
+        for element in &self.elements {
+            for index in partitioned_bloom_filter_indices::<H>(element, bin_count, hash_count) {
+                bins[index] = true;
+            }
+        }
+
+        bins
+    }
+}
+
+impl Multiset {
+    /// Uses `MultisetEncoding::Pair`.
+    pub fn to_partitioned_bloom_filter<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+        max_multiplicity: usize,
+    ) -> Vec<bool> {
+        self.to_partitioned_bloom_filter_with_encoding::<H>(
+            bin_count,
+            hash_count,
+            max_multiplicity,
+            MultisetEncoding::Pair,
+        )
+    }
+
+    pub fn to_partitioned_bloom_filter_with_encoding<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+        max_multiplicity: usize,
+        encoding: MultisetEncoding,
+    ) -> Vec<bool> {
+        let mut bins = vec![false; bin_count];
+
+        for (element, count) in &self.element_counts {
+            for i in 0..*count {
+                for index in partitioned_bloom_filter_copy_indices::<H>(
+                    element,
+                    i,
+                    max_multiplicity,
+                    bin_count,
+                    hash_count,
+                    encoding,
+                ) {
+                    bins[index] = true;
+                }
+            }
+        }
+
+        bins
+    }
+}
+
+/// Uses `MultisetEncoding::Pair`.
+pub fn partitioned_bloom_filter_retrieve_count<H: ElementHasher>(
+    bins: &[bool],
+    element: &usize,
+    hash_count: usize,
+    max_multiplicity: usize,
+) -> usize {
+    partitioned_bloom_filter_retrieve_count_with_encoding::<H>(
+        bins,
+        element,
+        hash_count,
+        max_multiplicity,
+        MultisetEncoding::Pair,
+    )
+}
+
+pub fn partitioned_bloom_filter_retrieve_count_with_encoding<H: ElementHasher>(
+    bins: &[bool],
+    element: &usize,
+    hash_count: usize,
+    max_multiplicity: usize,
+    encoding: MultisetEncoding,
+) -> usize {
+    for i in 0..max_multiplicity {
+        let contained = partitioned_bloom_filter_copy_indices::<H>(
+            element,
+            i,
+            max_multiplicity,
+            bins.len(),
+            hash_count,
+            encoding,
+        )
+        .all(|index| bins[index]);
+
+        if !contained {
+            return i;
+        }
+    }
+
+    max_multiplicity
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{gen_bloom_filter_params, MultisetEncoding, Xxh3Hasher};
+    use crate::multisets::Multiset;
+    use crate::partitioned_bloom_filters::{
+        gen_partitioned_bloom_filter_params, partitioned_bloom_filter_contains,
+        partitioned_bloom_filter_indices, partitioned_bloom_filter_retrieve_count,
+        partitioned_bloom_filter_retrieve_count_with_encoding,
+    };
+    use crate::sets::Set;
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_indices_one_per_slice() {
+        for element in 0..100 {
+            let indices: Vec<usize> =
+                partitioned_bloom_filter_indices::<H>(&element, 40, 4).collect();
+            for (slice, index) in indices.iter().enumerate() {
+                assert_eq!(index / 10, slice);
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_to_partitioned_bloom_filter() {
+        let set = Set::new(&[1, 3, 4]);
+        let bloom_filter = set.to_partitioned_bloom_filter::<H>(40, 2);
+
+        assert!(bloom_filter.iter().filter(|bin| **bin).count() <= 6);
+        assert!(partitioned_bloom_filter_contains::<H>(&bloom_filter, &1, 2));
+        assert!(partitioned_bloom_filter_contains::<H>(&bloom_filter, &3, 2));
+        assert!(partitioned_bloom_filter_contains::<H>(&bloom_filter, &4, 2));
+    }
+
+    #[test]
+    fn test_multiset_to_partitioned_bloom_filter() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let bloom_filter = multiset.to_partitioned_bloom_filter::<H>(200, 4, 2);
+
+        assert_eq!(
+            partitioned_bloom_filter_retrieve_count::<H>(&bloom_filter, &1, 4, 2),
+            1
+        );
+        assert_eq!(
+            partitioned_bloom_filter_retrieve_count::<H>(&bloom_filter, &3, 4, 2),
+            2
+        );
+        assert_eq!(
+            partitioned_bloom_filter_retrieve_count::<H>(&bloom_filter, &4, 4, 2),
+            1
+        );
+    }
+
+    #[test]
+    fn test_legacy_encoding() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let bloom_filter = multiset.to_partitioned_bloom_filter_with_encoding::<H>(
+            200,
+            4,
+            2,
+            MultisetEncoding::Legacy,
+        );
+
+        // The legacy encoding inserts element * max_multiplicity + i as a set element
+        let encoded = Set::new(&[2, 6, 7, 8]);
+        assert_eq!(
+            bloom_filter,
+            encoded.to_partitioned_bloom_filter::<H>(200, 4)
+        );
+        assert_eq!(
+            partitioned_bloom_filter_retrieve_count_with_encoding::<H>(
+                &bloom_filter,
+                &3,
+                4,
+                2,
+                MultisetEncoding::Legacy
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let (bin_count, hash_count) = gen_partitioned_bloom_filter_params(2f64.powf(-10.), 4096);
+        assert_eq!(bin_count % hash_count, 0);
+
+        // For large filters, partitioning needs about as many bins as a standard Bloom filter
+        let (standard_bin_count, _) = gen_bloom_filter_params(2f64.powf(-10.), 4096);
+        assert!(bin_count.abs_diff(standard_bin_count) < standard_bin_count / 100);
+    }
+
+    #[test]
+    fn test_error_rate() {
+        let (bin_count, hash_count) = gen_partitioned_bloom_filter_params(0.01, 5000);
+        let set: Set = (0..5000).collect();
+        let bloom_filter = set.to_partitioned_bloom_filter::<H>(bin_count, hash_count);
+
+        let false_positives = (5000..105_000)
+            .filter(|element| {
+                partitioned_bloom_filter_contains::<H>(&bloom_filter, element, hash_count)
+            })
+            .count();
+        assert!(
+            false_positives < 1300,
+            "{} false positives",
+            false_positives
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bin_count_not_multiple() {
+        Set::new(&[1]).to_partitioned_bloom_filter::<H>(41, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "the hash count must be at least 1")]
+    fn test_zero_hash_count() {
+        Set::new(&[1]).to_partitioned_bloom_filter::<H>(40, 0);
+    }
+}
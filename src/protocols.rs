@@ -0,0 +1,244 @@
+//! Plaintext simulations of Bloom-filter-based multi-party protocols. Every party inserts its
+//! set into a Bloom filter with the same parameters, the filters are summed bin-wise (which the
+//! secure protocols do under encryption, see `packing`), and the thresholded result is queried.
+//! The output is what the secure protocol should reveal, compared against the exact result.
+use crate::bloom_filters::{bloom_filter_contains, ElementHasher};
+use crate::error::Error;
+use crate::packing::{threshold_bins, try_aggregate_bloom_filters};
+use crate::sets::Set;
+
+/// The output of a protocol together with the exact result of the set operation.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ProtocolReport {
+    pub output: Set,
+    pub exact: Set,
+    /// Elements in the output but not in the exact result.
+    pub false_positives: usize,
+    /// Elements in the exact result but not in the output.
+    pub false_negatives: usize,
+}
+
+impl ProtocolReport {
+    pub fn new(output: Set, exact: Set) -> Self {
+        let correct = output.intersect(&exact).len();
+
+        ProtocolReport {
+            false_positives: output.len() - correct,
+            false_negatives: exact.len() - correct,
+            output,
+            exact,
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.false_positives == 0 && self.false_negatives == 0
+    }
+}
+
+/// The estimated size of the intersection together with the exact size.
+#[derive(PartialEq, Debug, Clone)]
+pub struct CardinalityReport {
+    pub estimate: f64,
+    pub exact: usize,
+}
+
+fn aggregate<H: ElementHasher>(
+    sets: &[Set],
+    bin_count: usize,
+    hash_count: usize,
+) -> Result<Vec<usize>, Error> {
+    let filters: Vec<Vec<bool>> = sets
+        .iter()
+        .map(|set| set.to_bloom_filter::<H>(bin_count, hash_count))
+        .collect();
+
+    try_aggregate_bloom_filters(&filters)
+}
+
+/// The elements of `candidates` that the thresholded aggregate contains.
+fn query<H: ElementHasher>(
+    candidates: impl IntoIterator<Item = usize>,
+    bins: &[bool],
+    hash_count: usize,
+) -> Set {
+    candidates
+        .into_iter()
+        .filter(|element| bloom_filter_contains::<H>(bins, element, hash_count))
+        .collect()
+}
+
+/// Multi-party private set intersection: the first party learns which of its elements are in
+/// the bins that every party set.
+pub fn psi<H: ElementHasher>(
+    sets: &[Set],
+    bin_count: usize,
+    hash_count: usize,
+) -> Result<ProtocolReport, Error> {
+    let counts = aggregate::<H>(sets, bin_count, hash_count)?;
+    let bins = threshold_bins(&counts, sets.len());
+
+    Ok(ProtocolReport::new(
+        query::<H>(sets[0].elements.iter().copied(), &bins, hash_count),
+        Set::try_intersection(sets)?,
+    ))
+}
+
+/// Threshold private set intersection: every party learns which of its elements are in the bins
+/// that at least `threshold` parties set, and the output is the union of what they learn.
+pub fn threshold_psi<H: ElementHasher>(
+    sets: &[Set],
+    bin_count: usize,
+    hash_count: usize,
+    threshold: usize,
+) -> Result<ProtocolReport, Error> {
+    if threshold == 0 || threshold > sets.len() {
+        return Err(Error::InvalidParameter(
+            "the threshold must be between 1 and the set count",
+        ));
+    }
+
+    let counts = aggregate::<H>(sets, bin_count, hash_count)?;
+    let bins = threshold_bins(&counts, threshold);
+
+    Ok(ProtocolReport::new(
+        query::<H>(Set::try_union(sets)?.elements, &bins, hash_count),
+        Set::threshold_intersection(sets, threshold),
+    ))
+}
+
+/// Multi-party private set union: the union filter is queried for every element of the
+/// universe, as no party knows the other elements.
+pub fn psu<H: ElementHasher>(
+    sets: &[Set],
+    bin_count: usize,
+    hash_count: usize,
+    universe: usize,
+) -> Result<ProtocolReport, Error> {
+    let counts = aggregate::<H>(sets, bin_count, hash_count)?;
+    let bins = threshold_bins(&counts, 1);
+
+    Ok(ProtocolReport::new(
+        query::<H>(0..universe, &bins, hash_count),
+        Set::try_union(sets)?,
+    ))
+}
+
+/// Private set intersection cardinality: only the number of bins that every party set is
+/// revealed, from which the intersection size is estimated as
+/// `-bin_count / hash_count * ln(1 - set_bins / bin_count)`.
+///
+/// A bin is also set in every filter when different elements of the parties collide in it, so
+/// the estimate counts these bins as well and overestimates the intersection size, more so for
+/// larger sets and fuller filters. Returns an error if every bin is set, as the estimate is then
+/// unbounded.
+pub fn psi_cardinality<H: ElementHasher>(
+    sets: &[Set],
+    bin_count: usize,
+    hash_count: usize,
+) -> Result<CardinalityReport, Error> {
+    let counts = aggregate::<H>(sets, bin_count, hash_count)?;
+    let set_bins = threshold_bins(&counts, sets.len())
+        .into_iter()
+        .filter(|bin| *bin)
+        .count();
+    if set_bins == bin_count {
+        return Err(Error::InvalidParameter(
+            "every bin is set, so the filters are too small to estimate the intersection size",
+        ));
+    }
+
+    let bin_count = bin_count as f64;
+    Ok(CardinalityReport {
+        estimate: -bin_count / hash_count as f64 * (1. - set_bins as f64 / bin_count).ln(),
+        exact: Set::try_intersection(sets)?.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{gen_bloom_filter_params, Xxh3Hasher};
+    use crate::error::Error;
+    use crate::protocols::{psi, psi_cardinality, psu, threshold_psi, ProtocolReport};
+    use crate::sets::{
+        gen_sets_with_intersection, gen_sets_with_threshold_intersection, gen_sets_with_union, Set,
+    };
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_report() {
+        let report = ProtocolReport::new(Set::new(&[1, 2, 3]), Set::new(&[2, 3, 4, 5]));
+        assert_eq!(report.false_positives, 1);
+        assert_eq!(report.false_negatives, 2);
+        assert!(!report.is_exact());
+    }
+
+    #[test]
+    fn test_psi() {
+        let sets = gen_sets_with_intersection(4, 100, 100_000, 30);
+        let (bin_count, hash_count) = gen_bloom_filter_params(0.001, 100);
+        let report = psi::<H>(&sets, bin_count, hash_count).unwrap();
+
+        assert_eq!(report.exact.len(), 30);
+        assert_eq!(report.false_negatives, 0);
+        assert!(report.false_positives <= 3);
+    }
+
+    #[test]
+    fn test_threshold_psi() {
+        let sets = gen_sets_with_threshold_intersection(5, 50, 10_000, 3, 10);
+        let report = threshold_psi::<H>(&sets, 2000, 5, 3).unwrap();
+
+        assert_eq!(report.exact.len(), 10);
+        assert_eq!(report.false_negatives, 0);
+        assert!(report.output.len() >= 10);
+
+        assert!(matches!(
+            threshold_psi::<H>(&sets, 2000, 5, 6),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_psu() {
+        let sets = gen_sets_with_union(3, 20, 1000, 40);
+        let report = psu::<H>(&sets, 2000, 5, 1000).unwrap();
+
+        assert_eq!(report.exact.len(), 40);
+        assert_eq!(report.false_negatives, 0);
+        assert!(report.false_positives < 10);
+    }
+
+    #[test]
+    fn test_psi_cardinality() {
+        let sets = gen_sets_with_intersection(3, 500, 1_000_000, 200);
+        let report = psi_cardinality::<H>(&sets, 20_000, 4).unwrap();
+
+        assert_eq!(report.exact, 200);
+        assert!(
+            (report.estimate - 200.).abs() < 30.,
+            "estimate {}",
+            report.estimate
+        );
+    }
+
+    #[test]
+    fn test_psi_cardinality_full_filters() {
+        let sets = gen_sets_with_intersection(2, 100, 1000, 50);
+        assert!(matches!(
+            psi_cardinality::<H>(&sets, 8, 4),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_no_sets() {
+        assert_eq!(
+            psi::<H>(&[], 100, 3),
+            Err(Error::NotEnoughSets {
+                required: 1,
+                given: 0
+            })
+        );
+    }
+}
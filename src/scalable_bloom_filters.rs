@@ -0,0 +1,265 @@
+//! Scalable Bloom filters (Almeida et al.), which do not need the set size up front.
+//!
+//! The filter is a chain of Bloom filters. Once a stage holds as many elements as it was sized
+//! for, a new stage is added with `growth_factor` times the capacity and `tightening_ratio` times
+//! the error rate. With an error rate of `max_error_rate * (1 - tightening_ratio)` for the first
+//! stage, the error rates of all stages sum to at most `max_error_rate`.
+use crate::bloom_filters::{
+    bloom_filter_contains, bloom_filter_indices, gen_bloom_filter_params, BloomFilter,
+    ElementHasher, MultisetEncoding,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScalableBloomFilter {
+    pub stages: Vec<BloomFilter>,
+    /// The number of elements inserted into the last stage.
+    pub last_stage_count: usize,
+    pub initial_capacity: usize,
+    pub max_error_rate: f64,
+    pub growth_factor: usize,
+    pub tightening_ratio: f64,
+    pub hasher_id: u8,
+}
+
+impl ScalableBloomFilter {
+    /// A filter that doubles its capacity and tightens the error rate by 0.9 per stage.
+    pub fn new<H: ElementHasher>(initial_capacity: usize, max_error_rate: f64) -> Self {
+        ScalableBloomFilter::with_parameters::<H>(initial_capacity, max_error_rate, 2, 0.9)
+    }
+
+    /// Panics if `initial_capacity` is zero, `growth_factor` is below 1, or `max_error_rate` or
+    /// `tightening_ratio` is not strictly between 0 and 1.
+    pub fn with_parameters<H: ElementHasher>(
+        initial_capacity: usize,
+        max_error_rate: f64,
+        growth_factor: usize,
+        tightening_ratio: f64,
+    ) -> Self {
+        assert!(initial_capacity > 0);
+        assert!(growth_factor >= 1);
+        assert!(max_error_rate > 0. && max_error_rate < 1.);
+        assert!(tightening_ratio > 0. && tightening_ratio < 1.);
+
+        let mut filter = ScalableBloomFilter {
+            stages: vec![],
+            last_stage_count: 0,
+            initial_capacity,
+            max_error_rate,
+            growth_factor,
+            tightening_ratio,
+            hasher_id: H::ID,
+        };
+        filter.add_stage();
+
+        filter
+    }
+
+    /// The number of elements stage `stage` is sized for.
+    pub fn stage_capacity(&self, stage: usize) -> usize {
+        self.initial_capacity * self.growth_factor.pow(stage as u32)
+    }
+
+    /// The false-positive rate stage `stage` is sized for.
+    pub fn stage_error_rate(&self, stage: usize) -> f64 {
+        self.max_error_rate
+            * (1. - self.tightening_ratio)
+            * self.tightening_ratio.powi(stage as i32)
+    }
+
+    /// The false-positive rate of the current stages once they are all filled to capacity, which
+    /// is below `max_error_rate`.
+    pub fn error_rate_bound(&self) -> f64 {
+        1. - (0..self.stages.len())
+            .map(|stage| 1. - self.stage_error_rate(stage))
+            .product::<f64>()
+    }
+
+    /// The number of elements inserted, not counting elements that the filter already
+    /// (possibly falsely) contained.
+    pub fn len(&self) -> usize {
+        (0..self.stages.len() - 1)
+            .map(|stage| self.stage_capacity(stage))
+            .sum::<usize>()
+            + self.last_stage_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn add_stage(&mut self) {
+        let stage = self.stages.len();
+        let (bin_count, hash_count) =
+            gen_bloom_filter_params(self.stage_error_rate(stage), self.stage_capacity(stage));
+
+        self.stages.push(BloomFilter {
+            bins: vec![false; bin_count],
+            hash_count,
+            max_multiplicity: None,
+            hasher_id: self.hasher_id,
+            distinct_indices: false,
+            multiset_encoding: MultisetEncoding::Pair,
+        });
+        self.last_stage_count = 0;
+    }
+
+    /// Inserts the element into the last stage, adding a stage first if it is full. Elements
+    /// that are already contained are skipped so that they do not use up capacity. Panics if
+    /// `H` is not the hasher that built this filter.
+    pub fn insert<H: ElementHasher>(&mut self, element: &usize) {
+        if self.contains::<H>(element) {
+            return;
+        }
+
+        if self.last_stage_count == self.stage_capacity(self.stages.len() - 1) {
+            self.add_stage();
+        }
+
+        let stage = self.stages.last_mut().unwrap();
+        for index in bloom_filter_indices::<H>(element, stage.bins.len(), stage.hash_count) {
+            stage.bins[index] = true;
+        }
+        self.last_stage_count += 1;
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        assert_eq!(H::ID, self.hasher_id);
+
+        self.stages
+            .iter()
+            .any(|stage| bloom_filter_contains::<H>(&stage.bins, element, stage.hash_count))
+    }
+
+    /// An immutable copy of the filter that can only be queried.
+    pub fn snapshot(&self) -> FrozenScalableBloomFilter {
+        FrozenScalableBloomFilter {
+            stages: self.stages.clone(),
+            hasher_id: self.hasher_id,
+        }
+    }
+}
+
+/// The stages of a scalable Bloom filter, fixed so that no more elements can be inserted. The
+/// stages cannot be merged into a single Bloom filter because each has its own bin count and hash
+/// count, so an element is contained if any stage contains it.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrozenScalableBloomFilter {
+    pub stages: Vec<BloomFilter>,
+    pub hasher_id: u8,
+}
+
+impl FrozenScalableBloomFilter {
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        assert_eq!(H::ID, self.hasher_id);
+
+        self.stages
+            .iter()
+            .any(|stage| bloom_filter_contains::<H>(&stage.bins, element, stage.hash_count))
+    }
+
+    /// The number of bins over all stages.
+    pub fn bin_count(&self) -> usize {
+        self.stages.iter().map(|stage| stage.bin_count()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{Blake3Hasher, Xxh3Hasher};
+    use crate::scalable_bloom_filters::ScalableBloomFilter;
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_grows() {
+        let mut filter = ScalableBloomFilter::new::<H>(100, 0.01);
+        assert!(filter.is_empty());
+
+        for element in 0..1000 {
+            filter.insert::<H>(&element);
+        }
+
+        // 100 + 200 + 400 < 1000 <= 100 + 200 + 400 + 800
+        assert_eq!(filter.stages.len(), 4);
+        assert!(filter.len() <= 1000 && filter.len() > 990);
+        assert!(filter.error_rate_bound() < 0.01);
+        for element in 0..1000 {
+            assert!(filter.contains::<H>(&element));
+        }
+    }
+
+    #[test]
+    fn test_error_rate() {
+        let mut filter = ScalableBloomFilter::new::<H>(64, 0.01);
+        for element in 0..10_000 {
+            filter.insert::<H>(&element);
+        }
+
+        let false_positives = (10_000..110_000)
+            .filter(|element| filter.contains::<H>(element))
+            .count();
+        assert!(
+            false_positives < 1000,
+            "{} false positives",
+            false_positives
+        );
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut filter = ScalableBloomFilter::with_parameters::<H>(10, 0.05, 4, 0.5);
+        for element in 0..100 {
+            filter.insert::<H>(&element);
+        }
+
+        let snapshot = filter.snapshot();
+        assert_eq!(snapshot.stages, filter.stages);
+        for element in 0..1000 {
+            assert_eq!(
+                snapshot.contains::<H>(&element),
+                filter.contains::<H>(&element)
+            );
+        }
+
+        filter.insert::<H>(&1000);
+        assert!(!snapshot.contains::<H>(&1000));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_hasher() {
+        let filter = ScalableBloomFilter::new::<H>(10, 0.01);
+        filter.contains::<Blake3Hasher>(&3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::scalable_bloom_filters::FrozenScalableBloomFilter;
+
+        let mut filter = ScalableBloomFilter::new::<H>(10, 0.01);
+        for element in 0..30 {
+            filter.insert::<H>(&element);
+        }
+
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ScalableBloomFilter>(&json).unwrap(),
+            filter
+        );
+
+        let snapshot = filter.snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(
+            serde_json::from_str::<FrozenScalableBloomFilter>(&json).unwrap(),
+            snapshot
+        );
+    }
+}
@@ -1,12 +1,17 @@
+use crate::error::Error;
 use rand::rngs::OsRng;
 use rand::seq::index::sample;
 use rand::seq::SliceRandom;
 use rand::Rng;
 use std::cmp;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 #[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Set {
     pub elements: HashSet<usize>,
 }
@@ -18,12 +23,28 @@ impl Set {
         }
     }
 
+    /// Panics if `element_count` exceeds `universe`.
     pub fn random(element_count: usize, universe: usize) -> Self {
-        Set {
-            elements: sample(&mut OsRng, universe, element_count)
-                .into_iter()
-                .collect(),
+        Set::try_random(element_count, universe).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_random(element_count: usize, universe: usize) -> Result<Self, Error> {
+        Set::try_random_with_rng(&mut OsRng, element_count, universe)
+    }
+
+    /// Same as `try_random`, but draws from `rng`, e.g. a seeded one for reproducible sets.
+    pub fn try_random_with_rng<R: Rng + ?Sized>(
+        rng: &mut R,
+        element_count: usize,
+        universe: usize,
+    ) -> Result<Self, Error> {
+        if element_count > universe {
+            return Err(Error::Infeasible("the element count exceeds the universe"));
         }
+
+        Ok(Set {
+            elements: sample(rng, universe, element_count).into_iter().collect(),
+        })
     }
 
     pub fn len(&self) -> usize {
@@ -48,14 +69,23 @@ impl Set {
         }
     }
 
+    /// Panics if `sets` is empty.
     pub fn intersection(sets: &[Set]) -> Set {
-        let mut result = sets[0].intersect(&sets[1]);
+        Set::try_intersection(sets).unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        for set in &sets[2..] {
+    pub fn try_intersection(sets: &[Set]) -> Result<Set, Error> {
+        let (first, rest) = sets.split_first().ok_or(Error::NotEnoughSets {
+            required: 1,
+            given: 0,
+        })?;
+
+        let mut result = first.clone();
+        for set in rest {
             result = result.intersect(set);
         }
 
-        result
+        Ok(result)
     }
 
     pub fn unify(&self, other: &Set) -> Set {
@@ -64,24 +94,55 @@ impl Set {
         }
     }
 
+    /// Panics if `sets` is empty.
     pub fn union(sets: &[Set]) -> Set {
-        let mut result = sets[0].unify(&sets[1]);
+        Set::try_union(sets).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_union(sets: &[Set]) -> Result<Set, Error> {
+        let (first, rest) = sets.split_first().ok_or(Error::NotEnoughSets {
+            required: 1,
+            given: 0,
+        })?;
 
-        for set in &sets[2..] {
+        let mut result = first.clone();
+        for set in rest {
             result = result.unify(set);
         }
 
-        result
+        Ok(result)
     }
 
-    pub fn to_bitset(&self, universe: usize) -> Vec<bool> {
-        let mut bitset = vec![false; universe];
+    /// The elements that are contained in at least `threshold` of the sets.
+    pub fn threshold_intersection(sets: &[Set], threshold: usize) -> Set {
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for set in sets {
+            for element in &set.elements {
+                *counts.entry(*element).or_insert(0) += 1;
+            }
+        }
+
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count >= threshold)
+            .map(|(element, _)| element)
+            .collect()
+    }
+
+    /// The size of the intersection divided by the size of the union, or 1 if both sets are empty.
+    pub fn jaccard(&self, other: &Set) -> f64 {
+        let intersection_size = self.elements.intersection(&other.elements).count();
+        let union_size = self.len() + other.len() - intersection_size;
 
-        for element in &self.elements {
-            bitset[*element] = true;
+        if union_size == 0 {
+            return 1.;
         }
 
-        bitset
+        intersection_size as f64 / union_size as f64
+    }
+
+    pub fn to_bitset(&self, universe: usize) -> Vec<bool> {
+        bitset_from_elements(self.elements.iter().copied(), universe)
     }
 
     pub fn from_bitset(bitset: &[bool]) -> Set {
@@ -96,36 +157,119 @@ impl Set {
     }
 }
 
+/// Builds the same bitset as `Set::to_bitset` from a stream of elements, which may contain
+/// duplicates, without collecting them first. Panics if an element is not below `universe`.
+pub fn bitset_from_elements<I: IntoIterator<Item = usize>>(
+    elements: I,
+    universe: usize,
+) -> Vec<bool> {
+    let mut bitset = vec![false; universe];
+
+    for element in elements {
+        bitset[element] = true;
+    }
+
+    bitset
+}
+
 /// Generates `set_count` random sets so that the size of the intersection is `intersection_size`.
+/// Panics if this is infeasible, see `try_gen_sets_with_intersection`.
 pub fn gen_sets_with_intersection(
     set_count: usize,
     element_count: usize,
     universe: usize,
     intersection_size: usize,
 ) -> Vec<Set> {
-    let intersection = Set::random(intersection_size, universe);
+    try_gen_sets_with_intersection(set_count, element_count, universe, intersection_size)
+        .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Generates `set_count` random sets so that the size of the intersection is `intersection_size`.
+/// Returns an error if the sets are larger than the universe, or if the universe is too small to
+/// keep the elements outside the intersection out of at least one set.
+///
+/// The other elements are drawn uniformly from the universe, rejecting those that would join the
+/// intersection. Only when the universe is smaller than `2 * element_count - intersection_size`,
+/// where rejection sampling can get stuck, are they spread over as few sets as possible instead,
+/// which makes them overlap more than uniform sampling would.
+pub fn try_gen_sets_with_intersection(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    intersection_size: usize,
+) -> Result<Vec<Set>, Error> {
+    try_gen_sets_with_intersection_with_rng(
+        &mut OsRng,
+        set_count,
+        element_count,
+        universe,
+        intersection_size,
+    )
+}
 
-    let mut sets: Vec<Set> = (0..set_count).map(|_| intersection.clone()).collect();
+/// Same as `try_gen_sets_with_intersection`, but draws from `rng`, e.g. a seeded one for
+/// reproducible sets.
+pub fn try_gen_sets_with_intersection_with_rng<R: Rng + ?Sized>(
+    rng: &mut R,
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    intersection_size: usize,
+) -> Result<Vec<Set>, Error> {
+    check_intersection_parameters(set_count, element_count, universe, intersection_size)?;
+
+    // While a set is filled, at most `element_count - 1` elements are already in it and at most
+    // `element_count - intersection_size` others are in all other sets, so some element is left
+    if universe >= 2 * element_count - intersection_size {
+        let intersection = Set::try_random_with_rng(rng, intersection_size, universe)?;
+        return Ok(sample_around_intersection(
+            rng,
+            set_count,
+            element_count,
+            universe,
+            &intersection,
+        ));
+    }
+
+    let pool_size = cmp::min(
+        universe - intersection_size,
+        set_count * (element_count - intersection_size),
+    );
+    let mut elements = sample(rng, universe, intersection_size + pool_size).into_iter();
+    let intersection: Set = elements.by_ref().take(intersection_size).collect();
+    let pool: Vec<usize> = elements.collect();
+
+    Ok(fill_around_intersection(
+        rng,
+        set_count,
+        element_count,
+        &intersection,
+        &pool,
+    ))
+}
+
+/// Fills `set_count` copies of `intersection` up to `element_count` elements with uniformly
+/// drawn elements, rejecting those that every other set already contains.
+fn sample_around_intersection<R: Rng + ?Sized>(
+    rng: &mut R,
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    intersection: &Set,
+) -> Vec<Set> {
+    let mut sets = vec![intersection.clone(); set_count];
 
-    // Fill with other random elements
     for i in 0..set_count {
         while sets[i].len() < element_count {
-            let element = OsRng.gen_range(0..universe);
+            let element = rng.gen_range(0..universe);
 
             // Check if at least one of the other sets does not contain this element
-            let mut can_insert = false;
-            for (j, set) in sets.iter().enumerate() {
-                if i == j {
-                    continue;
-                }
-
-                if !set.contains(&element) {
-                    can_insert = true;
-                    break;
-                }
-            }
+            let can_insert = sets
+                .iter()
+                .enumerate()
+                .any(|(j, set)| i != j && !set.contains(&element));
 
-            if can_insert && !sets[i].contains(&element) {
+            if can_insert {
                 sets[i].elements.insert(element);
             }
         }
@@ -134,21 +278,110 @@ pub fn gen_sets_with_intersection(
     sets
 }
 
-/// Generates `set_count` random sets so that the size of the union is `union_size`.
+pub(crate) fn check_intersection_parameters(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    intersection_size: usize,
+) -> Result<(), Error> {
+    if set_count == 0 {
+        return Err(Error::InvalidParameter("the set count must be at least 1"));
+    }
+    if intersection_size > element_count {
+        return Err(Error::Infeasible(
+            "the intersection is larger than the sets",
+        ));
+    }
+    if element_count > universe {
+        return Err(Error::Infeasible("the sets are larger than the universe"));
+    }
+
+    // Every element outside the intersection can be in at most `set_count - 1` sets
+    if set_count * (element_count - intersection_size)
+        > (set_count - 1) * (universe - intersection_size)
+    {
+        return Err(Error::Infeasible(
+            "the universe is too small to keep the other elements out of the intersection",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fills `set_count` copies of `intersection` up to `element_count` elements with elements of
+/// `pool`, without putting any of them in all sets. The pool must be large enough, which
+/// `check_intersection_parameters` ensures for a pool of
+/// `min(universe - intersection_size, set_count * (element_count - intersection_size))` elements.
+pub(crate) fn fill_around_intersection<R: Rng + ?Sized>(
+    rng: &mut R,
+    set_count: usize,
+    element_count: usize,
+    intersection: &Set,
+    pool: &[usize],
+) -> Vec<Set> {
+    let fill_count = element_count - intersection.len();
+    let occurrences =
+        distribute_occurrences(rng, set_count * fill_count, pool.len(), set_count - 1);
+
+    let mut sets = vec![intersection.clone(); set_count];
+    let mut capacities = vec![fill_count; set_count];
+    for (element, occurrence_count) in pool.iter().zip(occurrences) {
+        for index in take_emptiest_sets(rng, &mut capacities, occurrence_count) {
+            sets[index].elements.insert(*element);
+        }
+    }
+
+    sets
+}
+
+/// Generates `set_count` random sets so that the size of the union is `union_size`. Panics if
+/// this is infeasible, see `try_gen_sets_with_union`.
 pub fn gen_sets_with_union(
     set_count: usize,
     element_count: usize,
     universe: usize,
     union_size: usize,
 ) -> Vec<Set> {
-    let union = Set::random(union_size, universe);
+    try_gen_sets_with_union(set_count, element_count, universe, union_size)
+        .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Generates `set_count` random sets so that the size of the union is `union_size`. Returns an
+/// error if the union does not fit in the universe, or if the sets cannot cover the union or do
+/// not fit in it.
+pub fn try_gen_sets_with_union(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    union_size: usize,
+) -> Result<Vec<Set>, Error> {
+    try_gen_sets_with_union_with_rng(&mut OsRng, set_count, element_count, universe, union_size)
+}
+
+/// Same as `try_gen_sets_with_union`, but draws from `rng`, e.g. a seeded one for reproducible
+/// sets.
+pub fn try_gen_sets_with_union_with_rng<R: Rng + ?Sized>(
+    rng: &mut R,
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    union_size: usize,
+) -> Result<Vec<Set>, Error> {
+    check_union_parameters(set_count, element_count, universe, union_size)?;
+
+    // Sorted, so that the sets only depend on `rng` and not on the iteration order of a HashSet
+    let mut union: Vec<usize> = Set::try_random_with_rng(rng, union_size, universe)?
+        .elements
+        .into_iter()
+        .collect();
+    union.sort_unstable();
 
     let mut sets = vec![vec![]; set_count];
 
     // Distribute elements randomly
-    for element in &union.elements {
+    for element in &union {
         loop {
-            let index = OsRng.gen_range(0..set_count);
+            let index = rng.gen_range(0..set_count);
             if sets[index].len() < element_count {
                 sets[index].push(*element);
                 break;
@@ -158,34 +391,210 @@ pub fn gen_sets_with_union(
 
     // Fill with other random elements
     for set in sets.iter_mut() {
-        let mut elements = union.elements.iter().collect::<Vec<&usize>>();
-        elements.shuffle(&mut OsRng);
-        let mut shuffled_elements = elements.into_iter();
+        let mut elements = union.iter().collect::<Vec<&usize>>();
+        elements.shuffle(rng);
 
-        while set.len() < element_count {
-            let element = shuffled_elements.next().unwrap();
+        for element in elements {
+            if set.len() == element_count {
+                break;
+            }
             if !set.contains(element) {
                 set.push(*element);
             }
         }
     }
 
-    sets.iter().map(|set| Set::new(set)).collect()
+    Ok(sets.iter().map(|set| Set::new(set)).collect())
+}
+
+pub(crate) fn check_union_parameters(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    union_size: usize,
+) -> Result<(), Error> {
+    if set_count == 0 {
+        return Err(Error::InvalidParameter("the set count must be at least 1"));
+    }
+    if union_size > universe {
+        return Err(Error::Infeasible("the union is larger than the universe"));
+    }
+    if element_count > union_size {
+        return Err(Error::Infeasible("the sets are larger than the union"));
+    }
+    if union_size > set_count * element_count {
+        return Err(Error::Infeasible(
+            "the sets are too small to cover the union",
+        ));
+    }
+
+    Ok(())
 }
 
 /// Generates `set_count` random sets so that the size of the intersection is decided uniformly at random (it is at least 1).
+/// Panics if this is infeasible, see `try_gen_sets_with_uniform_intersection`.
 pub fn gen_sets_with_uniform_intersection(
     set_count: usize,
     element_count: usize,
     universe: usize,
 ) -> Vec<Set> {
+    try_gen_sets_with_uniform_intersection(set_count, element_count, universe)
+        .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Generates `set_count` random sets so that the size of the intersection is decided uniformly
+/// at random (it is at least 1). Returns an error if the sets are empty or larger than the
+/// universe.
+pub fn try_gen_sets_with_uniform_intersection(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+) -> Result<Vec<Set>, Error> {
+    if set_count == 0 {
+        return Err(Error::InvalidParameter("the set count must be at least 1"));
+    }
+    if element_count == 0 {
+        return Err(Error::Infeasible(
+            "the sets must have at least one element to intersect",
+        ));
+    }
+    if element_count > universe {
+        return Err(Error::Infeasible("the sets are larger than the universe"));
+    }
+
+    // The smallest feasible intersection, see `check_intersection_parameters`
     let intersection_size = OsRng.gen_range(
         cmp::max(
             1,
             (set_count * element_count) as isize - (universe * (set_count - 1)) as isize,
         ) as usize..=element_count,
     );
-    gen_sets_with_intersection(set_count, element_count, universe, intersection_size)
+    try_gen_sets_with_intersection(set_count, element_count, universe, intersection_size)
+}
+
+/// Generates `set_count` random sets of `element_count` elements so that exactly
+/// `threshold_intersection_size` elements are contained in at least `threshold` sets. Each of
+/// these elements is contained in exactly `threshold` sets, every other element in fewer than
+/// `threshold`. Panics if this is infeasible, see `try_gen_sets_with_threshold_intersection`.
+pub fn gen_sets_with_threshold_intersection(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    threshold: usize,
+    threshold_intersection_size: usize,
+) -> Vec<Set> {
+    try_gen_sets_with_threshold_intersection(
+        set_count,
+        element_count,
+        universe,
+        threshold,
+        threshold_intersection_size,
+    )
+    .unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Same as `gen_sets_with_threshold_intersection`, but returns an error if `threshold` is zero or
+/// larger than `set_count`, or if the universe is too small to fill the sets without pushing
+/// other elements over the threshold.
+pub fn try_gen_sets_with_threshold_intersection(
+    set_count: usize,
+    element_count: usize,
+    universe: usize,
+    threshold: usize,
+    threshold_intersection_size: usize,
+) -> Result<Vec<Set>, Error> {
+    if threshold == 0 || threshold > set_count {
+        return Err(Error::InvalidParameter(
+            "the threshold must be between 1 and the set count",
+        ));
+    }
+    if threshold_intersection_size > universe {
+        return Err(Error::Infeasible(
+            "the threshold intersection is larger than the universe",
+        ));
+    }
+    if threshold_intersection_size * threshold > set_count * element_count {
+        return Err(Error::Infeasible(
+            "the sets are too small to hold the threshold intersection",
+        ));
+    }
+
+    // The remaining slots are filled with elements that occur in at most `threshold - 1` sets
+    let other_occurrences = set_count * element_count - threshold_intersection_size * threshold;
+    let other_universe = universe - threshold_intersection_size;
+    if other_occurrences > (threshold - 1) * other_universe {
+        return Err(Error::Infeasible(
+            "the universe is too small to keep the other elements below the threshold",
+        ));
+    }
+
+    let other_element_count = cmp::min(other_universe, other_occurrences);
+    let mut elements = sample(
+        &mut OsRng,
+        universe,
+        threshold_intersection_size + other_element_count,
+    )
+    .into_iter();
+
+    let mut occurrences = vec![threshold; threshold_intersection_size];
+    occurrences.extend(distribute_occurrences(
+        &mut OsRng,
+        other_occurrences,
+        other_element_count,
+        threshold - 1,
+    ));
+
+    let mut sets = vec![Set::new(&[]); set_count];
+    let mut capacities = vec![element_count; set_count];
+    for (element, occurrence_count) in elements.by_ref().zip(occurrences) {
+        for index in take_emptiest_sets(&mut OsRng, &mut capacities, occurrence_count) {
+            sets[index].elements.insert(element);
+        }
+    }
+
+    Ok(sets)
+}
+
+/// Randomly spreads `total` occurrences over `element_count` elements, with at most `cap`
+/// occurrences per element.
+fn distribute_occurrences<R: Rng + ?Sized>(
+    rng: &mut R,
+    total: usize,
+    element_count: usize,
+    cap: usize,
+) -> Vec<usize> {
+    let mut occurrences = vec![0; element_count];
+    let mut open: Vec<usize> = (0..element_count).collect();
+
+    for _ in 0..total {
+        let position = rng.gen_range(0..open.len());
+        occurrences[open[position]] += 1;
+        if occurrences[open[position]] == cap {
+            open.swap_remove(position);
+        }
+    }
+
+    occurrences
+}
+
+/// Picks `count` distinct sets with the most remaining capacity, breaking ties randomly, and
+/// takes one unit of capacity from each. This keeps the capacities within one of each other, so
+/// the sets can always be filled exactly as long as the total capacity suffices.
+fn take_emptiest_sets<R: Rng + ?Sized>(
+    rng: &mut R,
+    capacities: &mut [usize],
+    count: usize,
+) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..capacities.len()).collect();
+    indices.shuffle(rng);
+    indices.sort_by_key(|index| cmp::Reverse(capacities[*index]));
+    indices.truncate(count);
+
+    for index in &indices {
+        capacities[*index] -= 1;
+    }
+
+    indices
 }
 
 impl FromIterator<usize> for Set {
@@ -197,8 +606,18 @@ impl FromIterator<usize> for Set {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec, clippy::map_clone)]
 mod tests {
-    use crate::sets::{gen_sets_with_intersection, gen_sets_with_union, Set};
+    use crate::error::Error;
+    use crate::sets::{
+        bitset_from_elements, gen_sets_with_intersection, gen_sets_with_threshold_intersection,
+        gen_sets_with_union, try_gen_sets_with_intersection,
+        try_gen_sets_with_intersection_with_rng, try_gen_sets_with_threshold_intersection,
+        try_gen_sets_with_uniform_intersection, try_gen_sets_with_union,
+        try_gen_sets_with_union_with_rng, Set,
+    };
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     #[test]
     fn test_random() {
@@ -232,12 +651,66 @@ mod tests {
         assert_eq!(Set::intersection(&vec![set1, set2, set3]), expected);
     }
 
+    #[test]
+    fn test_jaccard() {
+        let set1 = Set::new(&[1, 3, 4]);
+        let set2 = Set::new(&[1, 2, 4, 5]);
+
+        assert_eq!(set1.jaccard(&set2), 2. / 5.);
+        assert_eq!(set1.jaccard(&set1), 1.);
+        assert_eq!(Set::new(&[]).jaccard(&Set::new(&[])), 1.);
+    }
+
+    #[test]
+    fn test_threshold_intersection() {
+        let set1 = Set::new(&[1, 3, 4]);
+        let set2 = Set::new(&[1, 2, 4, 5]);
+        let set3 = Set::new(&[4, 3, 2]);
+        let sets = [set1, set2, set3];
+
+        assert_eq!(Set::threshold_intersection(&sets, 1), Set::union(&sets));
+        assert_eq!(
+            Set::threshold_intersection(&sets, 2),
+            Set::new(&[1, 2, 3, 4])
+        );
+        assert_eq!(
+            Set::threshold_intersection(&sets, 3),
+            Set::intersection(&sets)
+        );
+    }
+
+    #[test]
+    fn test_generators_with_rng() {
+        let generate = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (
+                Set::try_random_with_rng(&mut rng, 10, 100).unwrap(),
+                try_gen_sets_with_intersection_with_rng(&mut rng, 3, 20, 100, 5).unwrap(),
+                try_gen_sets_with_union_with_rng(&mut rng, 3, 20, 100, 40).unwrap(),
+            )
+        };
+
+        let (set, intersection_sets, union_sets) = generate(7);
+        assert_eq!(Set::intersection(&intersection_sets).len(), 5);
+        assert_eq!(Set::union(&union_sets).len(), 40);
+        assert_eq!((set, intersection_sets, union_sets), generate(7));
+        assert_ne!(generate(7), generate(8));
+    }
+
     #[test]
     fn test_to_bitset() {
         let set = Set::new(&vec![1, 3, 4]);
         assert_eq!(set.to_bitset(5), vec![false, true, false, true, true]);
     }
 
+    #[test]
+    fn test_bitset_from_elements() {
+        assert_eq!(
+            bitset_from_elements(vec![4, 1, 3, 1], 5),
+            Set::new(&[1, 3, 4]).to_bitset(5)
+        );
+    }
+
     #[test]
     fn test_from_bitset() {
         let bitset = vec![false, false, true, true, false, true];
@@ -251,6 +724,14 @@ mod tests {
         assert_eq!(Set::new(&elements), set);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let set = Set::new(&[1, 3, 4]);
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(serde_json::from_str::<Set>(&json).unwrap(), set);
+    }
+
     #[test]
     fn test_gen_sets_with_intersection() {
         let sets = gen_sets_with_intersection(3, 10, 100, 4);
@@ -266,4 +747,114 @@ mod tests {
         assert_eq!(sets[2].len(), 10);
         assert_ne!(sets[0], sets[1]);
     }
+
+    #[test]
+    fn test_gen_sets_with_threshold_intersection() {
+        let sets = gen_sets_with_threshold_intersection(5, 10, 100, 3, 6);
+        assert_eq!(Set::threshold_intersection(&sets, 3).len(), 6);
+        for set in &sets {
+            assert_eq!(set.len(), 10);
+        }
+    }
+
+    #[test]
+    fn test_gen_sets_with_threshold_intersection_tight() {
+        // Every other element has to occur in exactly two sets
+        let sets = gen_sets_with_threshold_intersection(4, 10, 18, 3, 4);
+        assert_eq!(Set::threshold_intersection(&sets, 3).len(), 4);
+        assert_eq!(Set::union(&sets).len(), 18);
+        for set in &sets {
+            assert_eq!(set.len(), 10);
+        }
+    }
+
+    #[test]
+    fn test_empty_intersection_and_union() {
+        let error = Error::NotEnoughSets {
+            required: 1,
+            given: 0,
+        };
+        assert_eq!(Set::try_intersection(&[]), Err(error.clone()));
+        assert_eq!(Set::try_union(&[]), Err(error));
+
+        let set = Set::new(&[1, 2]);
+        assert_eq!(
+            Set::try_intersection(std::slice::from_ref(&set)),
+            Ok(set.clone())
+        );
+        assert_eq!(Set::try_union(std::slice::from_ref(&set)), Ok(set));
+    }
+
+    #[test]
+    fn test_gen_sets_with_intersection_uniform() {
+        // With a large universe, the elements outside the intersection hardly ever overlap
+        let sets = gen_sets_with_intersection(3, 1000, 1_000_000_000, 0);
+        assert!(Set::intersection(&sets).is_empty());
+        assert!(Set::union(&sets).len() >= 2990);
+    }
+
+    #[test]
+    fn test_gen_sets_with_intersection_tight() {
+        // Every element outside the intersection has to occur in exactly two sets
+        let sets = try_gen_sets_with_intersection(3, 10, 13, 4).unwrap();
+        assert_eq!(Set::intersection(&sets).len(), 4);
+        assert_eq!(Set::union(&sets).len(), 13);
+        for set in &sets {
+            assert_eq!(set.len(), 10);
+        }
+
+        let sets = try_gen_sets_with_intersection(1, 7, 10, 7).unwrap();
+        assert_eq!(sets[0].len(), 7);
+    }
+
+    #[test]
+    fn test_infeasible_generators() {
+        assert!(matches!(Set::try_random(11, 10), Err(Error::Infeasible(_))));
+        assert!(matches!(
+            try_gen_sets_with_intersection(3, 10, 12, 4),
+            Err(Error::Infeasible(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_intersection(1, 10, 100, 4),
+            Err(Error::Infeasible(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_intersection(3, 4, 100, 5),
+            Err(Error::Infeasible(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_intersection(0, 4, 100, 2),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_union(3, 10, 100, 31),
+            Err(Error::Infeasible(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_union(3, 10, 100, 9),
+            Err(Error::Infeasible(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_union(3, 10, 20, 21),
+            Err(Error::Infeasible(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_uniform_intersection(3, 0, 100),
+            Err(Error::Infeasible(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_threshold_intersection(3, 10, 100, 4, 2),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_gen_sets_with_threshold_intersection(4, 10, 17, 3, 4),
+            Err(Error::Infeasible(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "infeasible parameters")]
+    fn test_gen_sets_with_intersection_panics() {
+        gen_sets_with_intersection(3, 10, 12, 4);
+    }
 }
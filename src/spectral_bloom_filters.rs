@@ -0,0 +1,258 @@
+//! Spectral Bloom filters (Cohen and Matias), which keep a counter per bin and add an element's
+//! multiplicity to each of its bins, so that a count is retrieved with `hash_count` probes
+//! instead of one membership test per copy.
+//!
+//! With minimum selection, the count is the smallest counter of the element, which never
+//! underestimates. With recurring minimum, an element whose smallest counter occurs only once
+//! among its counters is likely to share that bin with another element, so it is also kept in
+//! a smaller secondary filter that is used for its queries instead. This gives far fewer
+//! overestimates, but an element whose minimum only stops recurring after its insertion is
+//! looked up in a secondary filter it was never added to, and may then be underestimated.
+use crate::bloom_filters::{bloom_filter_indices, ElementHasher};
+use crate::multisets::Multiset;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpectralBloomFilter {
+    pub counters: Vec<usize>,
+    pub hash_count: usize,
+    /// The secondary counters of the recurring-minimum variant, `None` for minimum selection.
+    pub secondary_counters: Option<Vec<usize>>,
+    pub hasher_id: u8,
+}
+
+/// The unique indices of the element, so that a bin is never counted twice for one element.
+fn unique_indices<H: ElementHasher>(
+    element: &usize,
+    bin_count: usize,
+    hash_count: usize,
+) -> Vec<usize> {
+    let mut indices: Vec<usize> =
+        bloom_filter_indices::<H>(element, bin_count, hash_count).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// The smallest counter and whether it occurs more than once.
+fn minimum(counters: &[usize], indices: &[usize]) -> (usize, bool) {
+    let minimum = indices.iter().map(|index| counters[*index]).min().unwrap();
+    let occurrences = indices
+        .iter()
+        .filter(|index| counters[**index] == minimum)
+        .count();
+
+    (minimum, occurrences > 1)
+}
+
+impl SpectralBloomFilter {
+    /// Panics if `hash_count` is 0.
+    pub fn new_minimum_selection<H: ElementHasher>(bin_count: usize, hash_count: usize) -> Self {
+        assert!(hash_count > 0, "the hash count must be at least 1");
+
+        SpectralBloomFilter {
+            counters: vec![0; bin_count],
+            hash_count,
+            secondary_counters: None,
+            hasher_id: H::ID,
+        }
+    }
+
+    /// The secondary filter only holds the elements without a recurring minimum, so it can be
+    /// much smaller than the primary one. Panics if `hash_count` is 0.
+    pub fn new_recurring_minimum<H: ElementHasher>(
+        bin_count: usize,
+        hash_count: usize,
+        secondary_bin_count: usize,
+    ) -> Self {
+        assert!(hash_count > 0, "the hash count must be at least 1");
+
+        SpectralBloomFilter {
+            counters: vec![0; bin_count],
+            hash_count,
+            secondary_counters: Some(vec![0; secondary_bin_count]),
+            hasher_id: H::ID,
+        }
+    }
+
+    pub fn bin_count(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Adds `count` copies of the element. Panics if `H` is not the hasher that built this
+    /// filter.
+    pub fn insert<H: ElementHasher>(&mut self, element: &usize, count: usize) {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let indices = unique_indices::<H>(element, self.counters.len(), self.hash_count);
+        for index in &indices {
+            self.counters[*index] += count;
+        }
+
+        if let Some(secondary_counters) = &mut self.secondary_counters {
+            let (primary_minimum, recurring) = minimum(&self.counters, &indices);
+            if recurring {
+                return;
+            }
+
+            let secondary_indices =
+                unique_indices::<H>(element, secondary_counters.len(), self.hash_count);
+            let (secondary_minimum, _) = minimum(secondary_counters, &secondary_indices);
+            if secondary_minimum > 0 {
+                for index in &secondary_indices {
+                    secondary_counters[*index] += count;
+                }
+            } else {
+                // The element enters the secondary filter with its best estimate so far
+                for index in &secondary_indices {
+                    secondary_counters[*index] =
+                        std::cmp::max(secondary_counters[*index], primary_minimum);
+                }
+            }
+        }
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn retrieve_count<H: ElementHasher>(&self, element: &usize) -> usize {
+        assert_eq!(H::ID, self.hasher_id);
+
+        let indices = unique_indices::<H>(element, self.counters.len(), self.hash_count);
+        let (primary_minimum, recurring) = minimum(&self.counters, &indices);
+
+        match &self.secondary_counters {
+            Some(secondary_counters) if !recurring => {
+                let secondary_indices =
+                    unique_indices::<H>(element, secondary_counters.len(), self.hash_count);
+                let (secondary_minimum, _) = minimum(secondary_counters, &secondary_indices);
+                if secondary_minimum > 0 {
+                    std::cmp::min(secondary_minimum, primary_minimum)
+                } else {
+                    primary_minimum
+                }
+            }
+            _ => primary_minimum,
+        }
+    }
+
+    /// Panics if `H` is not the hasher that built this filter.
+    pub fn contains<H: ElementHasher>(&self, element: &usize) -> bool {
+        self.retrieve_count::<H>(element) > 0
+    }
+}
+
+impl Multiset {
+    pub fn to_spectral_bloom_filter<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+    ) -> SpectralBloomFilter {
+        let mut filter = SpectralBloomFilter::new_minimum_selection::<H>(bin_count, hash_count);
+        for (element, count) in &self.element_counts {
+            filter.insert::<H>(element, *count);
+        }
+
+        filter
+    }
+
+    pub fn to_recurring_minimum_bloom_filter<H: ElementHasher>(
+        &self,
+        bin_count: usize,
+        hash_count: usize,
+        secondary_bin_count: usize,
+    ) -> SpectralBloomFilter {
+        let mut filter = SpectralBloomFilter::new_recurring_minimum::<H>(
+            bin_count,
+            hash_count,
+            secondary_bin_count,
+        );
+        for (element, count) in &self.element_counts {
+            filter.insert::<H>(element, *count);
+        }
+
+        filter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{Blake3Hasher, Xxh3Hasher};
+    use crate::multisets::Multiset;
+    use crate::spectral_bloom_filters::SpectralBloomFilter;
+
+    type H = Xxh3Hasher;
+
+    #[test]
+    fn test_minimum_selection() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 5, 2]);
+        let filter = multiset.to_spectral_bloom_filter::<H>(100, 3);
+
+        assert_eq!(filter.retrieve_count::<H>(&1), 1);
+        assert_eq!(filter.retrieve_count::<H>(&3), 5);
+        assert_eq!(filter.retrieve_count::<H>(&4), 2);
+        assert!(!filter.contains::<H>(&2));
+    }
+
+    #[test]
+    fn test_never_underestimates() {
+        // A small filter, so that many counters are shared
+        let multiset = Multiset::random(500, 1_000_000, 20);
+        let filter = multiset.to_spectral_bloom_filter::<H>(1000, 3);
+
+        for (element, count) in &multiset.element_counts {
+            assert!(filter.retrieve_count::<H>(element) >= *count);
+        }
+    }
+
+    #[test]
+    fn test_recurring_minimum_more_accurate() {
+        let multiset = Multiset::random(10_000, 10_000_000, 20);
+        let filter = multiset.to_spectral_bloom_filter::<H>(40_000, 3);
+        let recurring_filter = multiset.to_recurring_minimum_bloom_filter::<H>(40_000, 3, 20_000);
+
+        let errors = |filter: &SpectralBloomFilter| -> usize {
+            multiset
+                .element_counts
+                .iter()
+                .filter(|(element, count)| filter.retrieve_count::<H>(element) != **count)
+                .count()
+        };
+        assert!(errors(&recurring_filter) < errors(&filter));
+    }
+
+    #[test]
+    fn test_insert_incrementally() {
+        let mut filter = SpectralBloomFilter::new_recurring_minimum::<H>(100, 3, 50);
+        filter.insert::<H>(&7, 2);
+        filter.insert::<H>(&7, 3);
+
+        assert_eq!(filter.retrieve_count::<H>(&7), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "the hash count must be at least 1")]
+    fn test_zero_hash_count() {
+        SpectralBloomFilter::new_recurring_minimum::<H>(100, 0, 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_hasher() {
+        let filter = SpectralBloomFilter::new_minimum_selection::<H>(100, 3);
+        filter.retrieve_count::<Blake3Hasher>(&3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 5, 2]);
+        let filter = multiset.to_recurring_minimum_bloom_filter::<H>(100, 3, 50);
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SpectralBloomFilter>(&json).unwrap(),
+            filter
+        );
+    }
+}
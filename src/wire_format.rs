@@ -0,0 +1,352 @@
+//! Versioned binary format for shipping Bloom filters between processes.
+//!
+//! All integers are little-endian. A filter is encoded as:
+//!
+//! | bytes | content                                                       |
+//! |-------|---------------------------------------------------------------|
+//! | 4     | magic `SMBF`                                                  |
+//! | 1     | format version                                                |
+//! | 1     | hasher id (`ElementHasher::ID`)                               |
+//! | 1     | flags, bit 0 is set if `max_multiplicity` follows, bit 1 if   |
+//! |       | the filter uses distinct indices, bit 2 if it is built from a |
+//! |       | multiset with the pair encoding (otherwise the legacy one)    |
+//! | 8     | bin count                                                     |
+//! | 8     | hash count                                                    |
+//! | 8     | max multiplicity, only present if flag bit 0 is set           |
+//! | ...   | bins packed 8 per byte, bin `i` is bit `i % 8` of byte `i / 8` |
+use crate::bloom_filters::{BloomFilter, ElementHasher, MultisetEncoding};
+use std::convert::TryInto;
+use std::fmt;
+
+pub const MAGIC: [u8; 4] = *b"SMBF";
+pub const VERSION: u8 = 1;
+
+const FLAG_MAX_MULTIPLICITY: u8 = 1;
+const FLAG_DISTINCT_INDICES: u8 = 2;
+const FLAG_PAIR_ENCODING: u8 = 4;
+const KNOWN_FLAGS: u8 = FLAG_MAX_MULTIPLICITY | FLAG_DISTINCT_INDICES | FLAG_PAIR_ENCODING;
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum DecodeError {
+    /// The input ends before the header or the bins are complete.
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownFlags(u8),
+    /// The filter was built with hasher `found` but is queried with hasher `expected`.
+    HasherMismatch {
+        expected: u8,
+        found: u8,
+    },
+    /// The header describes a filter that cannot exist, e.g. one without bins or hashes.
+    InvalidParameters,
+    /// There are bytes left after the bins, or the padding bits of the last byte are set.
+    TrailingData,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "the encoded Bloom filter is truncated"),
+            DecodeError::BadMagic => write!(f, "the input is not an encoded Bloom filter"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported Bloom filter format version {}", version)
+            }
+            DecodeError::UnknownFlags(flags) => write!(f, "unknown header flags {:#04x}", flags),
+            DecodeError::HasherMismatch { expected, found } => write!(
+                f,
+                "the Bloom filter was built with hasher {} but is queried with hasher {}",
+                found, expected
+            ),
+            DecodeError::InvalidParameters => write!(f, "invalid Bloom filter parameters"),
+            DecodeError::TrailingData => {
+                write!(f, "unexpected data after the encoded Bloom filter")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub fn encode_bloom_filter(bloom_filter: &BloomFilter) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(39 + bloom_filter.bin_count().div_ceil(8));
+
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(VERSION);
+    bytes.push(bloom_filter.hasher_id);
+    let mut flags = 0;
+    if bloom_filter.max_multiplicity.is_some() {
+        flags |= FLAG_MAX_MULTIPLICITY;
+    }
+    if bloom_filter.distinct_indices {
+        flags |= FLAG_DISTINCT_INDICES;
+    }
+    if bloom_filter.max_multiplicity.is_some()
+        && bloom_filter.multiset_encoding == MultisetEncoding::Pair
+    {
+        flags |= FLAG_PAIR_ENCODING;
+    }
+    bytes.push(flags);
+    bytes.extend_from_slice(&(bloom_filter.bin_count() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(bloom_filter.hash_count as u64).to_le_bytes());
+    if let Some(max_multiplicity) = bloom_filter.max_multiplicity {
+        bytes.extend_from_slice(&(max_multiplicity as u64).to_le_bytes());
+    }
+
+    bytes.extend(bloom_filter.bins.chunks(8).map(|chunk| {
+        chunk
+            .iter()
+            .enumerate()
+            .fold(0u8, |byte, (i, bin)| byte | ((*bin as u8) << i))
+    }));
+
+    bytes
+}
+
+pub fn decode_bloom_filter(bytes: &[u8]) -> Result<BloomFilter, DecodeError> {
+    let mut reader = Reader { bytes };
+
+    if reader.take(4)? != MAGIC {
+        return Err(DecodeError::BadMagic);
+    }
+
+    let version = reader.take(1)?[0];
+    if version != VERSION {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+
+    let hasher_id = reader.take(1)?[0];
+
+    let flags = reader.take(1)?[0];
+    if flags & !KNOWN_FLAGS != 0 {
+        return Err(DecodeError::UnknownFlags(flags));
+    }
+
+    let bin_count = reader.take_usize()?;
+    let hash_count = reader.take_usize()?;
+    let max_multiplicity = if flags & FLAG_MAX_MULTIPLICITY != 0 {
+        Some(reader.take_usize()?)
+    } else {
+        None
+    };
+
+    let distinct_indices = flags & FLAG_DISTINCT_INDICES != 0;
+    let pair_encoding = flags & FLAG_PAIR_ENCODING != 0;
+    check_parameters(bin_count, hash_count, max_multiplicity, distinct_indices)?;
+    if pair_encoding && max_multiplicity.is_none() {
+        return Err(DecodeError::InvalidParameters);
+    }
+
+    let packed = reader.take(bin_count.div_ceil(8))?;
+    if !reader.bytes.is_empty() {
+        return Err(DecodeError::TrailingData);
+    }
+    if bin_count % 8 != 0 && packed[packed.len() - 1] >> (bin_count % 8) != 0 {
+        return Err(DecodeError::TrailingData);
+    }
+
+    Ok(BloomFilter {
+        bins: (0..bin_count)
+            .map(|i| (packed[i / 8] >> (i % 8)) & 1 == 1)
+            .collect(),
+        hash_count,
+        max_multiplicity,
+        hasher_id,
+        distinct_indices,
+        // Filters of sets do not use the encoding and are built with the pair encoding
+        multiset_encoding: if pair_encoding || max_multiplicity.is_none() {
+            MultisetEncoding::Pair
+        } else {
+            MultisetEncoding::Legacy
+        },
+    })
+}
+
+/// Rejects parameters of a filter that cannot exist. Deserializing a `BloomFilter` with serde
+/// performs the same checks.
+pub(crate) fn check_parameters(
+    bin_count: usize,
+    hash_count: usize,
+    max_multiplicity: Option<usize>,
+    distinct_indices: bool,
+) -> Result<(), DecodeError> {
+    if bin_count == 0
+        || hash_count == 0
+        || max_multiplicity == Some(0)
+        || (distinct_indices && hash_count > bin_count)
+    {
+        return Err(DecodeError::InvalidParameters);
+    }
+
+    Ok(())
+}
+
+/// Same as `decode_bloom_filter`, but rejects filters that were not built with `H`.
+pub fn decode_bloom_filter_for<H: ElementHasher>(bytes: &[u8]) -> Result<BloomFilter, DecodeError> {
+    let bloom_filter = decode_bloom_filter(bytes)?;
+
+    if bloom_filter.hasher_id != H::ID {
+        return Err(DecodeError::HasherMismatch {
+            expected: H::ID,
+            found: bloom_filter.hasher_id,
+        });
+    }
+
+    Ok(bloom_filter)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < count {
+            return Err(DecodeError::Truncated);
+        }
+
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_usize(&mut self) -> Result<usize, DecodeError> {
+        let value = u64::from_le_bytes(self.take(8)?.try_into().unwrap());
+        value.try_into().map_err(|_| DecodeError::InvalidParameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filters::{Blake3Hasher, BloomFilter, MultisetEncoding, Xxh3Hasher};
+    use crate::multisets::Multiset;
+    use crate::sets::Set;
+    use crate::wire_format::{
+        decode_bloom_filter, decode_bloom_filter_for, encode_bloom_filter, DecodeError,
+    };
+
+    #[test]
+    fn test_round_trip() {
+        let set = Set::new(&[1, 3, 4]);
+        let bloom_filter = BloomFilter::from_set::<Xxh3Hasher>(&set, 21, 2);
+        let bytes = encode_bloom_filter(&bloom_filter);
+
+        assert_eq!(bytes.len(), 23 + 3);
+        assert_eq!(
+            decode_bloom_filter_for::<Xxh3Hasher>(&bytes).unwrap(),
+            bloom_filter
+        );
+    }
+
+    #[test]
+    fn test_round_trip_multiset() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let bloom_filter = BloomFilter::from_multiset::<Xxh3Hasher>(&multiset, 64, 2, 2);
+        let bytes = encode_bloom_filter(&bloom_filter);
+
+        assert_eq!(bytes.len(), 31 + 8);
+        assert_eq!(decode_bloom_filter(&bytes).unwrap(), bloom_filter);
+    }
+
+    #[test]
+    fn test_round_trip_legacy_encoding() {
+        let multiset = Multiset::new(&[1, 3, 4], &[1, 2, 1]);
+        let bloom_filter = BloomFilter::from_multiset_with_encoding::<Xxh3Hasher>(
+            &multiset,
+            64,
+            2,
+            2,
+            MultisetEncoding::Legacy,
+            false,
+        );
+        let bytes = encode_bloom_filter(&bloom_filter);
+
+        assert_eq!(bytes[6], 1);
+        assert_eq!(decode_bloom_filter(&bytes).unwrap(), bloom_filter);
+    }
+
+    #[test]
+    fn test_round_trip_distinct() {
+        let mut bloom_filter = BloomFilter::new::<Xxh3Hasher>(20, 4).with_distinct_indices(true);
+        for element in [1, 3, 4] {
+            bloom_filter.insert::<Xxh3Hasher>(&element);
+        }
+        let bytes = encode_bloom_filter(&bloom_filter);
+
+        assert_eq!(bytes[6], 2);
+        assert_eq!(decode_bloom_filter(&bytes).unwrap(), bloom_filter);
+
+        // Distinct indices cannot exceed the bin count
+        let mut too_many_hashes = bytes;
+        too_many_hashes[15] = 21;
+        assert_eq!(
+            decode_bloom_filter(&too_many_hashes),
+            Err(DecodeError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_hasher_mismatch() {
+        let bloom_filter = BloomFilter::from_set::<Xxh3Hasher>(&Set::new(&[1, 3, 4]), 20, 2);
+        let bytes = encode_bloom_filter(&bloom_filter);
+
+        assert_eq!(
+            decode_bloom_filter_for::<Blake3Hasher>(&bytes),
+            Err(DecodeError::HasherMismatch {
+                expected: 3,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_headers() {
+        let bloom_filter = BloomFilter::from_set::<Xxh3Hasher>(&Set::new(&[1, 3, 4]), 20, 2);
+        let bytes = encode_bloom_filter(&bloom_filter);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(decode_bloom_filter(&bad_magic), Err(DecodeError::BadMagic));
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 2;
+        assert_eq!(
+            decode_bloom_filter(&bad_version),
+            Err(DecodeError::UnsupportedVersion(2))
+        );
+
+        let mut bad_flags = bytes.clone();
+        bad_flags[6] = 8;
+        assert_eq!(
+            decode_bloom_filter(&bad_flags),
+            Err(DecodeError::UnknownFlags(8))
+        );
+
+        // Only multiset filters have an encoding
+        let mut pair_encoding = bytes.clone();
+        pair_encoding[6] = 4;
+        assert_eq!(
+            decode_bloom_filter(&pair_encoding),
+            Err(DecodeError::InvalidParameters)
+        );
+
+        assert_eq!(
+            decode_bloom_filter(&bytes[..bytes.len() - 1]),
+            Err(DecodeError::Truncated)
+        );
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            decode_bloom_filter(&trailing),
+            Err(DecodeError::TrailingData)
+        );
+
+        let mut padding = bytes;
+        *padding.last_mut().unwrap() |= 0x80;
+        assert_eq!(
+            decode_bloom_filter(&padding),
+            Err(DecodeError::TrailingData)
+        );
+    }
+}